@@ -0,0 +1,489 @@
+use serde::Serialize;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+// Semantic prompt (shell integration) sequences: ESC ] 133 ; <kind> [; ...]
+const OSC_133: &[u8] = b"\x1b]133;";
+// Longest unterminated OSC 133 sequence carried over to the next chunk
+const MAX_PENDING_OSC: usize = 256;
+// Longest unterminated escape sequence AnsiStripper carries over
+const MAX_PENDING_ESCAPE: usize = 4096;
+
+/// Length in bytes of the escape sequence starting at `input[start]` (which
+/// must be ESC), and whether it's complete rather than cut off by the end of
+/// input. Handles CSI (colors, cursor moves), OSC/DCS-style strings
+/// terminated by BEL or ST, charset designators, and single-character escapes.
+fn scan_escape(input: &[u8], start: usize) -> (usize, bool) {
+    let mut i = start + 1;
+
+    // Lone ESC at the end of input
+    if i >= input.len() {
+        return (1, false);
+    }
+
+    let complete = match input[i] {
+        // CSI: ESC [ params intermediates final
+        b'[' => {
+            i += 1;
+            while i < input.len() && !(0x40..=0x7e).contains(&input[i]) {
+                i += 1;
+            }
+            i += 1;
+            i <= input.len()
+        }
+        // OSC, DCS, SOS, PM, APC: terminated by BEL or ST (ESC \)
+        b']' | b'P' | b'X' | b'^' | b'_' => {
+            i += 1;
+            let mut terminated = false;
+            while i < input.len() {
+                if input[i] == BEL {
+                    i += 1;
+                    terminated = true;
+                    break;
+                }
+                if input[i] == ESC && input.get(i + 1) == Some(&b'\\') {
+                    i += 2;
+                    terminated = true;
+                    break;
+                }
+                i += 1;
+            }
+            terminated
+        }
+        // nF escapes (e.g. charset designators "ESC ( B"): intermediates then final
+        0x20..=0x2f => {
+            i += 1;
+            while i < input.len() && (0x20..=0x2f).contains(&input[i]) {
+                i += 1;
+            }
+            i += 1;
+            i <= input.len()
+        }
+        // Single-character escapes (ESC 7, ESC =, ESC M, ...)
+        _ => {
+            i += 1;
+            true
+        }
+    };
+
+    (i.min(input.len()) - start, complete)
+}
+
+fn escape_len(input: &[u8], start: usize) -> usize {
+    scan_escape(input, start).0
+}
+
+/// Strips escape sequences from output fed chunk by chunk, carrying a
+/// sequence cut off at the end of one chunk over to the next
+#[derive(Default)]
+pub struct AnsiStripper {
+    pending: Vec<u8>,
+}
+
+impl AnsiStripper {
+    /// The plain bytes of `chunk`. A multibyte character can still be split
+    /// across calls, so decode the concatenated output.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(chunk);
+
+        let mut out = Vec::with_capacity(data.len());
+        let mut i = 0;
+        while i < data.len() {
+            if data[i] != ESC {
+                out.push(data[i]);
+                i += 1;
+                continue;
+            }
+            let (len, complete) = scan_escape(&data, i);
+            if !complete && data.len() - i <= MAX_PENDING_ESCAPE {
+                self.pending = data[i..].to_vec();
+                break;
+            }
+            i += len;
+        }
+        out
+    }
+}
+
+/// Strip ANSI escape sequences from raw terminal output, leaving plain text
+pub fn strip_ansi(input: &[u8]) -> String {
+    String::from_utf8_lossy(&AnsiStripper::default().push(input)).to_string()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    fg: Option<String>,
+    bg: Option<String>,
+}
+
+impl Style {
+    fn css(&self) -> String {
+        let mut css = String::new();
+        if self.bold {
+            css.push_str("font-weight:bold;");
+        }
+        if self.italic {
+            css.push_str("font-style:italic;");
+        }
+        if self.underline {
+            css.push_str("text-decoration:underline;");
+        }
+        if let Some(fg) = &self.fg {
+            css.push_str(&format!("color:{};", fg));
+        }
+        if let Some(bg) = &self.bg {
+            css.push_str(&format!("background-color:{};", bg));
+        }
+        css
+    }
+}
+
+// xterm's default 16-color palette
+const BASIC_COLORS: [&str; 16] = [
+    "#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+    "#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+];
+
+fn color_256(n: u8) -> String {
+    match n {
+        0..=15 => BASIC_COLORS[n as usize].to_string(),
+        16..=231 => {
+            let levels = [0, 95, 135, 175, 215, 255];
+            let n = n - 16;
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                levels[(n / 36) as usize],
+                levels[((n / 6) % 6) as usize],
+                levels[(n % 6) as usize]
+            )
+        }
+        _ => {
+            let gray = 8 + (n - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+/// Parse an extended color (`5;n` or `2;r;g;b`) following a 38/48 code,
+/// returning the color and how many params were consumed. The color is None
+/// when a component is out of range, so the params are skipped but ignored.
+fn extended_color(params: &[u16]) -> Option<(Option<String>, usize)> {
+    let component = |i: usize| params.get(i).map(|&c| u8::try_from(c).ok());
+    match params.first()? {
+        5 => Some((component(1)?.map(color_256), 2)),
+        2 => {
+            let (r, g, b) = (component(1)?, component(2)?, component(3)?);
+            let color = r
+                .zip(g)
+                .zip(b)
+                .map(|((r, g), b)| format!("#{:02x}{:02x}{:02x}", r, g, b));
+            Some((color, 4))
+        }
+        _ => None,
+    }
+}
+
+fn apply_sgr(style: &mut Style, params: &[u16]) {
+    if params.is_empty() {
+        *style = Style::default();
+        return;
+    }
+
+    let mut i = 0;
+    while i < params.len() {
+        match params[i] {
+            0 => *style = Style::default(),
+            1 => style.bold = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => style.bold = false,
+            23 => style.italic = false,
+            24 => style.underline = false,
+            code @ 30..=37 => style.fg = Some(BASIC_COLORS[(code - 30) as usize].to_string()),
+            code @ 90..=97 => style.fg = Some(BASIC_COLORS[(code - 90 + 8) as usize].to_string()),
+            code @ 40..=47 => style.bg = Some(BASIC_COLORS[(code - 40) as usize].to_string()),
+            code @ 100..=107 => {
+                style.bg = Some(BASIC_COLORS[(code - 100 + 8) as usize].to_string())
+            }
+            39 => style.fg = None,
+            49 => style.bg = None,
+            code @ (38 | 48) => {
+                if let Some((color, used)) = extended_color(&params[i + 1..]) {
+                    match color {
+                        Some(color) if code == 38 => style.fg = Some(color),
+                        Some(color) => style.bg = Some(color),
+                        None => {}
+                    }
+                    i += used;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn flush_span(html: &mut String, text: &mut Vec<u8>, style: &Style) {
+    if text.is_empty() {
+        return;
+    }
+    let escaped = escape_html(&String::from_utf8_lossy(text));
+    if *style == Style::default() {
+        html.push_str(&escaped);
+    } else {
+        html.push_str(&format!(
+            "<span style=\"{}\">{}</span>",
+            style.css(),
+            escaped
+        ));
+    }
+    text.clear();
+}
+
+/// Convert raw terminal output to HTML, turning SGR sequences into inline-
+/// styled spans and dropping every other escape sequence
+pub fn ansi_to_html(input: &[u8]) -> String {
+    let mut html = String::new();
+    let mut text = Vec::new();
+    let mut style = Style::default();
+    let mut i = 0;
+
+    while i < input.len() {
+        match input[i] {
+            ESC => {
+                let len = escape_len(input, i);
+                let seq = &input[i..i + len];
+                if seq.len() >= 3 && seq[1] == b'[' && seq[seq.len() - 1] == b'm' {
+                    let params: Vec<u16> = String::from_utf8_lossy(&seq[2..seq.len() - 1])
+                        .split([';', ':'])
+                        .map(|p| p.parse().unwrap_or(0))
+                        .collect();
+                    let mut next = style.clone();
+                    apply_sgr(&mut next, &params);
+                    if next != style {
+                        flush_span(&mut html, &mut text, &style);
+                        style = next;
+                    }
+                }
+                i += len;
+            }
+            // Carriage returns only matter for in-place redraws
+            b'\r' => i += 1,
+            byte => {
+                text.push(byte);
+                i += 1;
+            }
+        }
+    }
+    flush_span(&mut html, &mut text, &style);
+
+    html
+}
+
+/// A shell integration marker. Kinds: "A" prompt start, "B" command start,
+/// "C" command output start, "D" command finished.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptMark {
+    pub kind: String,
+    pub exit_code: Option<i32>, // Reported by "D" markers
+    pub offset: usize,          // Position in the output stream just past the marker
+}
+
+/// Length of an OSC body and its terminator (BEL or ST), or None while the
+/// terminator hasn't arrived yet
+fn osc_terminator(body: &[u8]) -> Option<(usize, usize)> {
+    let end = body.iter().position(|&b| b == BEL || b == ESC)?;
+    match body[end] {
+        BEL => Some((end, 1)),
+        _ => match body.get(end + 1) {
+            Some(b'\\') => Some((end, 2)),
+            Some(_) => Some((end, 0)),
+            None => None,
+        },
+    }
+}
+
+fn parse_prompt_mark(body: &[u8], offset: usize) -> Option<PromptMark> {
+    let body = String::from_utf8_lossy(body);
+    let mut params = body.split(';');
+    let kind = params.next().filter(|k| ["A", "B", "C", "D"].contains(k))?;
+    let exit_code = match kind {
+        "D" => params.next().and_then(|code| code.parse().ok()),
+        _ => None,
+    };
+    Some(PromptMark {
+        kind: kind.to_string(),
+        exit_code,
+        offset,
+    })
+}
+
+/// Finds OSC 133 markers in a PTY output stream fed chunk by chunk,
+/// including sequences split across chunk boundaries
+#[derive(Default)]
+pub struct PromptMarkScanner {
+    pending: Vec<u8>,
+    offset: usize, // Total bytes scanned so far
+}
+
+impl PromptMarkScanner {
+    pub fn scan(&mut self, chunk: &[u8]) -> Vec<PromptMark> {
+        // Stream offset of data[0] (pending bytes were already counted)
+        let base = self.offset - self.pending.len();
+        self.offset += chunk.len();
+
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(chunk);
+
+        let mut marks = Vec::new();
+        let mut i = 0;
+        while let Some(pos) = data[i..].iter().position(|&b| b == ESC) {
+            let start = i + pos;
+            let rest = &data[start..];
+            if !rest.starts_with(OSC_133) {
+                // Possibly the beginning of a marker cut off by the chunk end
+                if rest.len() < OSC_133.len() && OSC_133.starts_with(rest) {
+                    self.pending = rest.to_vec();
+                    break;
+                }
+                i = start + 1;
+                continue;
+            }
+
+            let body_start = start + OSC_133.len();
+            let Some((body_len, terminator_len)) = osc_terminator(&data[body_start..]) else {
+                if rest.len() <= MAX_PENDING_OSC {
+                    self.pending = rest.to_vec();
+                }
+                break;
+            };
+            let end = body_start + body_len + terminator_len;
+            if let Some(mark) =
+                parse_prompt_mark(&data[body_start..body_start + body_len], base + end)
+            {
+                marks.push(mark);
+            }
+            i = end;
+        }
+
+        marks
+    }
+}
+
+#[tauri::command]
+pub fn strip_ansi_escapes(text: String) -> String {
+    strip_ansi(text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured from `ls --color=always` on a dir, a symlink, and an executable
+    const LS_COLOR: &[u8] = b"a.txt\n\x1b[0m\x1b[01;34mdir\x1b[0m\n\x1b[01;36mlink\x1b[0m\n\
+        \x1b[01;32mscript.sh\x1b[0m\n";
+
+    // Captured from `git -c color.ui=always diff`
+    const GIT_DIFF: &[u8] = b"\x1b[1mdiff --git a/f b/f\x1b[m\n\
+        \x1b[1mindex 00750ed..d00491f 100644\x1b[m\n\
+        \x1b[1m--- a/f\x1b[m\n\x1b[1m+++ b/f\x1b[m\n\
+        \x1b[36m@@ -1 +1 @@\x1b[m\n\x1b[31m-3\x1b[m\n\x1b[32m+\x1b[m\x1b[32m1\x1b[m\n";
+
+    // 256-color and truecolor SGR, resets, an OSC 8 hyperlink (ST), a title
+    // (BEL), and cursor movement from a progress line
+    const MIXED: &[u8] = b"\x1b]0;build\x07\x1b[38;5;208mwarn\x1b[0m \
+        \x1b[48;2;30;30;30m\x1b[38;2;255;100;0mhot\x1b[39;49m \
+        \x1b]8;;file:///tmp/a.txt\x1b\\a.txt\x1b]8;;\x1b\\ 50%\x1b[2K\x1b[1G100%\x1b[m\n";
+
+    #[test]
+    fn strips_ls_colors() {
+        assert_eq!(strip_ansi(LS_COLOR), "a.txt\ndir\nlink\nscript.sh\n");
+    }
+
+    #[test]
+    fn strips_git_diff_colors() {
+        assert_eq!(
+            strip_ansi(GIT_DIFF),
+            "diff --git a/f b/f\nindex 00750ed..d00491f 100644\n--- a/f\n+++ b/f\n\
+             @@ -1 +1 @@\n-3\n+1\n"
+        );
+    }
+
+    #[test]
+    fn strips_extended_colors_osc_and_cursor_moves() {
+        assert_eq!(strip_ansi(MIXED), "warn hot a.txt 50%100%\n");
+    }
+
+    #[test]
+    fn stripper_handles_sequences_split_across_chunks() {
+        for sample in [LS_COLOR, GIT_DIFF, MIXED] {
+            let expected = strip_ansi(sample);
+            for split in 0..=sample.len() {
+                let mut stripper = AnsiStripper::default();
+                let mut out = stripper.push(&sample[..split]);
+                out.extend(stripper.push(&sample[split..]));
+                assert_eq!(
+                    String::from_utf8_lossy(&out),
+                    expected,
+                    "split at {}",
+                    split
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn html_keeps_256_and_truecolor() {
+        let html = ansi_to_html(MIXED);
+        assert!(html.contains("<span style=\"color:#ff8700;\">warn</span>"));
+        assert!(html.contains("<span style=\"color:#ff6400;background-color:#1e1e1e;\">hot</span>"));
+        // Reset sequences return to unstyled text
+        assert!(html.ends_with("50%100%\n"));
+    }
+
+    #[test]
+    fn html_ignores_out_of_range_colors() {
+        let html = ansi_to_html(b"\x1b[38;2;300;0;0;1mbold\x1b[38;5;256;4mline\x1b[m");
+        assert!(html.contains("<span style=\"font-weight:bold;\">bold</span>"));
+        assert!(html
+            .contains("<span style=\"font-weight:bold;text-decoration:underline;\">line</span>"));
+        assert!(!html.contains("color:"));
+    }
+
+    #[test]
+    fn html_styles_git_diff() {
+        let html = ansi_to_html(GIT_DIFF);
+        assert!(html.contains("<span style=\"color:#00cdcd;\">@@ -1 +1 @@</span>"));
+        assert!(html.contains("<span style=\"color:#cd0000;\">-3</span>"));
+        assert!(html.contains("<span style=\"font-weight:bold;\">--- a/f</span>"));
+    }
+
+    #[test]
+    fn prompt_marks_split_across_chunks() {
+        let stream = b"$ \x1b]133;C\x07ok\n\x1b]133;D;1\x1b\\";
+        for split in 0..=stream.len() {
+            let mut scanner = PromptMarkScanner::default();
+            let mut marks = scanner.scan(&stream[..split]);
+            marks.extend(scanner.scan(&stream[split..]));
+            let kinds: Vec<_> = marks
+                .iter()
+                .map(|m| (m.kind.as_str(), m.exit_code))
+                .collect();
+            assert_eq!(kinds, [("C", None), ("D", Some(1))], "split at {}", split);
+            assert_eq!(marks[1].offset, stream.len());
+        }
+    }
+}
@@ -1,12 +1,18 @@
-use serde_json::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
-fn get_config_path() -> PathBuf {
+pub fn get_config_dir() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("aterm")
-        .join("config.json")
+}
+
+fn get_config_path() -> PathBuf {
+    get_config_dir().join("config.json")
 }
 
 fn ensure_config_dir() -> std::io::Result<()> {
@@ -17,6 +23,78 @@ fn ensure_config_dir() -> std::io::Result<()> {
     Ok(())
 }
 
+/// Read state the backend keeps in its own file in the config dir. It stays
+/// out of config.json, which the frontend overwrites wholesale on every save.
+pub fn load_state_file<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let content = fs::read_to_string(get_config_dir().join(name)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_state_file<T: Serialize>(name: &str, state: &T) -> Result<(), String> {
+    ensure_config_dir().map_err(|e| e.to_string())?;
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(get_config_dir().join(name), content).map_err(|e| e.to_string())
+}
+
+/// Schema version written by this build. Bump it together with a new entry
+/// in MIGRATIONS whenever the stored config's shape changes.
+pub const CONFIG_SCHEMA_VERSION: u64 = 1;
+
+// Top-level key holding the schema version a config was written with
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+type Migration = fn(&mut Map<String, Value>);
+
+// MIGRATIONS[n] upgrades a version-n config to version n + 1
+const MIGRATIONS: [Migration; CONFIG_SCHEMA_VERSION as usize] = [
+    // 0 -> 1: configs from before versioning just get stamped
+    |_| {},
+];
+
+// Version the config was migrated from during this run, if it was
+static MIGRATED_FROM: Mutex<Option<u64>> = Mutex::new(None);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionedConfig {
+    config: Value,
+    schema_version: u64,
+    migrated_from: Option<u64>, // Set when an older config was upgraded on load
+}
+
+fn write_config(config: &Value) -> Result<(), String> {
+    ensure_config_dir().map_err(|e| e.to_string())?;
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(get_config_path(), content).map_err(|e| e.to_string())
+}
+
+/// Bring an older config up to CONFIG_SCHEMA_VERSION, backing up the original
+/// file first. Configs from a newer build are left untouched.
+fn migrate_config(config: &mut Value, original: &str) -> Result<(), String> {
+    let Some(object) = config.as_object_mut() else {
+        return Ok(());
+    };
+    let version = object
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    if version >= CONFIG_SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let backup = get_config_path().with_extension("json.bak");
+    fs::write(&backup, original).map_err(|e| e.to_string())?;
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(object);
+    }
+    object.insert(SCHEMA_VERSION_KEY.to_string(), CONFIG_SCHEMA_VERSION.into());
+    write_config(config)?;
+
+    *MIGRATED_FROM.lock().unwrap() = Some(version);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn load_config() -> Result<Value, String> {
     let config_path = get_config_path();
@@ -27,15 +105,95 @@ pub fn load_config() -> Result<Value, String> {
     }
 
     let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let config: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let mut config: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    migrate_config(&mut config, &content)?;
     Ok(config)
 }
 
+/// The config along with its schema version and, if it was upgraded during
+/// this run, the version it was migrated from
 #[tauri::command]
-pub fn save_config(config: Value) -> Result<(), String> {
-    ensure_config_dir().map_err(|e| e.to_string())?;
-    let config_path = get_config_path();
-    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, content).map_err(|e| e.to_string())?;
-    Ok(())
+pub fn load_config_versioned() -> Result<VersionedConfig, String> {
+    let config = load_config()?;
+    let schema_version = config
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(CONFIG_SCHEMA_VERSION);
+    Ok(VersionedConfig {
+        config,
+        schema_version,
+        migrated_from: *MIGRATED_FROM.lock().unwrap(),
+    })
+}
+
+#[tauri::command]
+pub fn save_config(mut config: Value) -> Result<(), String> {
+    if let Some(object) = config.as_object_mut() {
+        object
+            .entry(SCHEMA_VERSION_KEY)
+            .or_insert(CONFIG_SCHEMA_VERSION.into());
+    }
+    write_config(&config)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownConfigKey {
+    key: String,
+    suggestion: Option<String>, // Closest known key, for likely typos
+}
+
+// Largest edit distance still treated as a typo of a known key (shorter keys
+// allow proportionally fewer edits)
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+// Ignore case and separators so theme_name matches themeName
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Top-level keys in the stored config that the frontend doesn't know about,
+/// each with the closest known key when it looks like a typo
+#[tauri::command]
+pub fn diff_config_keys(known_keys: Vec<String>) -> Result<Vec<UnknownConfigKey>, String> {
+    let config = load_config()?;
+    let Some(object) = config.as_object() else {
+        return Ok(Vec::new());
+    };
+
+    Ok(object
+        .keys()
+        .filter(|key| *key != SCHEMA_VERSION_KEY && !known_keys.contains(key))
+        .map(|key| {
+            let normalized = normalize_key(key);
+            let max_distance = (normalized.len() / 3).clamp(1, MAX_SUGGESTION_DISTANCE);
+            let suggestion = known_keys
+                .iter()
+                .map(|known| (edit_distance(&normalized, &normalize_key(known)), known))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .min_by_key(|(distance, _)| *distance)
+                .map(|(_, known)| known.clone());
+            UnknownConfigKey {
+                key: key.clone(),
+                suggestion,
+            }
+        })
+        .collect())
 }
@@ -1,6 +1,11 @@
+use crate::config::{load_config, load_state_file, save_state_file};
+use crate::file_ops::is_binary_file;
+use crate::git_timing::TimedCommand;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +23,12 @@ pub struct ProjectFileEntry {
     name: String,
     path: String, // Relative path from project root
     is_dir: bool,
+    is_symlink: bool,
+    target: Option<String>,          // Symlink target as written in the link
+    resolved_target: Option<String>, // Canonical target, None if broken
+    is_broken_link: bool,
+    is_git_repo: bool, // Nested repository or submodule
+    is_submodule: bool,
 }
 
 // Directories to always filter out in project explorer
@@ -93,12 +104,152 @@ pub fn get_home_dir() -> String {
         .to_string()
 }
 
+// Upper bound on suggestions returned by complete_path
+const MAX_COMPLETIONS: usize = 50;
+
+/// Expand a leading `~` and any `$VAR` / `${VAR}` references in a path-like
+/// value against the current environment. Unset variables are left as written.
+pub fn expand_path(value: &str) -> String {
+    let mut expanded = match value.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+            .map(|home| format!("{}{}", home.to_string_lossy(), rest))
+            .unwrap_or_else(|| value.to_string()),
+        _ => value.to_string(),
+    };
+
+    let mut result = String::with_capacity(expanded.len());
+    while let Some(dollar) = expanded.find('$') {
+        result.push_str(&expanded[..dollar]);
+        let rest = &expanded[dollar + 1..];
+        let (name, consumed) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+
+        match std::env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(var) => result.push_str(&var),
+            None => result.push_str(&expanded[dollar..dollar + 1 + consumed]),
+        }
+        expanded = expanded[dollar + 1 + consumed..].to_string();
+    }
+    result.push_str(&expanded);
+
+    result
+}
+
+/// Suggest completions for a partially typed path: entries in its parent
+/// directory whose names start with the last component, directories first.
+/// Relative paths are resolved against the home directory.
+#[tauri::command]
+pub fn complete_path(partial: String) -> Result<Vec<DirEntry>, String> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let expanded = home.join(expand_path(&partial));
+
+    // "foo/" completes inside foo; "foo/ba" completes "ba" inside foo
+    let (dir, prefix) = if partial.is_empty() || partial.ends_with('/') || partial == "~" {
+        (expanded, String::new())
+    } else {
+        let prefix = expanded
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let dir = expanded.parent().map(Path::to_path_buf).unwrap_or(expanded);
+        (dir, prefix)
+    };
+
+    // Nothing to suggest while the typed parent doesn't exist yet
+    let Ok(read_dir) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut entries: Vec<DirEntry> = read_dir
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Hidden entries only when explicitly asked for
+            if !name.starts_with(&prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let path = entry.path();
+            let is_dir = path.is_dir();
+            Some(DirEntry {
+                is_git_repo: is_dir && path.join(".git").exists(),
+                name,
+                path: path.to_string_lossy().to_string(),
+                is_dir,
+            })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
+    entries.truncate(MAX_COMPLETIONS);
+
+    Ok(entries)
+}
+
+/// Resolve where the directory picker should open. `hint` may be "home",
+/// "last" (the folder the most recently added project lives in), a project
+/// name, or a literal path. Falls back to the home directory when the hint
+/// doesn't resolve to a directory.
+#[tauri::command]
+pub fn resolve_start_directory(hint: Option<String>) -> String {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let hint = hint.map(|h| h.trim().to_string()).unwrap_or_default();
+    // Projects are appended as they're added, so the last one is the newest
+    let project_paths = || -> Vec<(String, PathBuf)> {
+        let Ok(config) = load_config() else {
+            return Vec::new();
+        };
+        let Some(projects) = config.get("projects").and_then(|p| p.as_array()) else {
+            return Vec::new();
+        };
+        projects
+            .iter()
+            .filter_map(|project| {
+                let name = project.get("name")?.as_str()?.to_string();
+                let path = project.get("path")?.as_str()?;
+                Some((name, PathBuf::from(expand_path(path))))
+            })
+            .collect()
+    };
+
+    let candidate = match hint.as_str() {
+        "" | "home" => None,
+        "last" => project_paths()
+            .pop()
+            .and_then(|(_, path)| path.parent().map(Path::to_path_buf)),
+        _ => project_paths()
+            .into_iter()
+            .find(|(name, _)| *name == hint)
+            .map(|(_, path)| path)
+            .or_else(|| Some(PathBuf::from(expand_path(&hint)))),
+    };
+
+    candidate
+        .filter(|p| p.is_dir())
+        .unwrap_or(home)
+        .to_string_lossy()
+        .to_string()
+}
+
 /// List directory contents for project file explorer
 /// Returns entries with paths relative to the project root
 #[tauri::command]
 pub fn list_project_directory(
     root: String,
     relative_path: Option<String>,
+    respect_gitignore: Option<bool>,
 ) -> Result<Vec<ProjectFileEntry>, String> {
     let root_path = PathBuf::from(&root);
     let full_path = match &relative_path {
@@ -107,6 +258,14 @@ pub fn list_project_directory(
     };
 
     let mut entries = Vec::new();
+    // Only asked of git when a nested repo actually shows up
+    let mut submodules: Option<HashSet<String>> = None;
+    let ignored = if respect_gitignore.unwrap_or(true) {
+        gitignored_paths(&root)
+    } else {
+        None
+    };
+    let ignored_dirs = ignored_dirs();
 
     let read_dir = fs::read_dir(&full_path).map_err(|e| e.to_string())?;
 
@@ -122,20 +281,45 @@ pub fn list_project_directory(
         let is_dir = path.is_dir();
 
         // Skip ignored directories
-        if is_dir && IGNORED_DIRS.contains(&name.as_str()) {
+        if is_dir && ignored_dirs.contains(&name) {
             continue;
         }
 
+        let is_symlink = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+        let (target, resolved_target) = if is_symlink {
+            symlink_targets(&path)
+        } else {
+            (None, None)
+        };
+        let is_broken_link = is_symlink && resolved_target.is_none();
+
         // Calculate relative path from root
         let rel_path = match &relative_path {
             Some(rel) if !rel.is_empty() => format!("{}/{}", rel, name),
             _ => name.clone(),
         };
 
+        if is_gitignored(&ignored, &rel_path, is_dir) {
+            continue;
+        }
+
+        // A nested repo has a .git directory; submodules have a .git file
+        let is_git_repo = is_dir && path.join(".git").exists();
+        let is_submodule = is_git_repo
+            && submodules
+                .get_or_insert_with(|| submodule_paths(&root))
+                .contains(&rel_path);
+
         entries.push(ProjectFileEntry {
             name,
             path: rel_path,
             is_dir,
+            is_symlink,
+            target,
+            resolved_target,
+            is_broken_link,
+            is_git_repo,
+            is_submodule,
         });
     }
 
@@ -149,6 +333,115 @@ pub fn list_project_directory(
     Ok(entries)
 }
 
+/// The raw target of a symlink and, if it exists, the canonical path it
+/// points at. Relative targets are resolved against the link's directory.
+fn symlink_targets(link: &Path) -> (Option<String>, Option<String>) {
+    let Ok(raw) = fs::read_link(link) else {
+        return (None, None);
+    };
+    let resolved = link
+        .parent()
+        .map_or_else(|| raw.clone(), |dir| dir.join(&raw))
+        .canonicalize()
+        .ok()
+        .map(|t| t.to_string_lossy().to_string());
+    (Some(raw.to_string_lossy().to_string()), resolved)
+}
+
+/// Built-in ignored directories merged with the `extraIgnoredDirs` config
+/// key. Entries prefixed with `!` remove a built-in (e.g. "!build").
+pub fn ignored_dirs() -> HashSet<String> {
+    let mut dirs: HashSet<String> = IGNORED_DIRS.iter().map(|d| d.to_string()).collect();
+    let extra = load_config().ok().and_then(|config| {
+        config.get("extraIgnoredDirs")?.as_array().map(|entries| {
+            entries
+                .iter()
+                .filter_map(|e| e.as_str().map(|s| s.trim().to_string()))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    for entry in extra.unwrap_or_default() {
+        match entry.strip_prefix('!') {
+            Some(removed) => {
+                dirs.remove(removed);
+            }
+            None if !entry.is_empty() => {
+                dirs.insert(entry);
+            }
+            None => {}
+        }
+    }
+
+    dirs
+}
+
+/// Effective set of directory names hidden from the explorer and search
+#[tauri::command]
+pub fn get_ignored_dirs() -> Vec<String> {
+    let mut dirs: Vec<String> = ignored_dirs().into_iter().collect();
+    dirs.sort();
+    dirs
+}
+
+/// Paths under `root` (relative to it) excluded by git's ignore rules, which
+/// covers nested .gitignore files, negations, info/exclude and global
+/// excludes. Ignored directories appear once with a trailing slash. None when
+/// `root` isn't inside a git repository.
+fn gitignored_paths(root: &str) -> Option<HashSet<String>> {
+    let output = std::process::Command::new("git")
+        .args([
+            "-C",
+            root,
+            "ls-files",
+            "-z",
+            "--others",
+            "--ignored",
+            "--exclude-standard",
+            "--directory",
+        ])
+        .timed_output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    // NUL-separated so paths with newlines or quoted characters come through as-is
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| path.to_string())
+            .collect(),
+    )
+}
+
+fn is_gitignored(ignored: &Option<HashSet<String>>, rel_path: &str, is_dir: bool) -> bool {
+    let Some(ignored) = ignored else {
+        return false;
+    };
+    let rel_path = rel_path.replace('\\', "/");
+    ignored.contains(&rel_path) || (is_dir && ignored.contains(&format!("{}/", rel_path)))
+}
+
+/// Paths (relative to the repo root) of the submodules registered in `root`
+fn submodule_paths(root: &str) -> HashSet<String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", root, "submodule", "status"])
+        .timed_output();
+
+    match output {
+        // Each line: <status char><sha> <path> (<describe>)
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.get(1..)?.split_whitespace().nth(1))
+            .map(|p| p.to_string())
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
 /// Check if a dotfile is important enough to show
 fn is_important_dotfile(name: &str) -> bool {
     matches!(
@@ -173,18 +466,47 @@ fn is_important_dotfile(name: &str) -> bool {
 
 /// Recursively list all files in a project (for file search)
 #[tauri::command]
-pub fn list_all_project_files(root: String) -> Result<Vec<String>, String> {
+pub fn list_all_project_files(
+    root: String,
+    respect_gitignore: Option<bool>,
+) -> Result<Vec<String>, String> {
     let root_path = PathBuf::from(&root);
     let mut files = Vec::new();
-    collect_files_recursive(&root_path, &root_path, &mut files)?;
+    let mut visited = HashSet::new();
+    let filter = FileFilter {
+        gitignored: if respect_gitignore.unwrap_or(true) {
+            gitignored_paths(&root)
+        } else {
+            None
+        },
+        ignored_dirs: ignored_dirs(),
+        include_hidden: false,
+    };
+    collect_files_recursive(&root_path, &root_path, &filter, &mut files, &mut visited)?;
     Ok(files)
 }
 
+// What list_all_project_files leaves out
+struct FileFilter {
+    gitignored: Option<HashSet<String>>,
+    ignored_dirs: HashSet<String>,
+    include_hidden: bool, // Keep all dotfiles, not just the important ones
+}
+
 fn collect_files_recursive(
     root: &PathBuf,
     current: &PathBuf,
+    filter: &FileFilter,
     files: &mut Vec<String>,
+    visited: &mut HashSet<PathBuf>,
 ) -> Result<(), String> {
+    // Track canonical paths so symlinks pointing back at an ancestor (or at
+    // an already-listed directory) don't cause infinite recursion
+    let canonical = fs::canonicalize(current).map_err(|e| e.to_string())?;
+    if !visited.insert(canonical) {
+        return Ok(());
+    }
+
     let read_dir = fs::read_dir(current).map_err(|e| e.to_string())?;
 
     for entry in read_dir.filter_map(|e| e.ok()) {
@@ -192,27 +514,345 @@ fn collect_files_recursive(
         let name = entry.file_name().to_string_lossy().to_string();
 
         // Skip hidden files (except important dotfiles)
-        if name.starts_with('.') && !is_important_dotfile(&name) {
+        if name.starts_with('.') && !filter.include_hidden && !is_important_dotfile(&name) {
             continue;
         }
 
         let is_dir = path.is_dir();
 
         // Skip ignored directories
-        if is_dir && IGNORED_DIRS.contains(&name.as_str()) {
+        if is_dir && filter.ignored_dirs.contains(&name) {
+            continue;
+        }
+
+        let Ok(rel_path) = path.strip_prefix(root) else {
+            continue;
+        };
+        let rel_path = rel_path.to_string_lossy().to_string();
+        if is_gitignored(&filter.gitignored, &rel_path, is_dir) {
             continue;
         }
 
         if is_dir {
             // Recurse into directory
-            collect_files_recursive(root, &path, files)?;
+            collect_files_recursive(root, &path, filter, files, visited)?;
         } else {
             // Add file with relative path
-            if let Ok(rel_path) = path.strip_prefix(root) {
-                files.push(rel_path.to_string_lossy().to_string());
-            }
+            files.push(rel_path);
         }
     }
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FileSearchOptions {
+    pub case_sensitive: bool,
+    pub include_hidden: bool,
+    pub match_path: bool, // Match against the relative path rather than the file name
+}
+
+impl Default for FileSearchOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            include_hidden: false,
+            match_path: true,
+        }
+    }
+}
+
+// Results returned by search_project_files unless the caller asks otherwise
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+// File the picker's last search options are kept in
+const FILE_SEARCH_FILE: &str = "file-search.json";
+
+/// Search defaults saved by the picker
+#[tauri::command]
+pub fn get_file_search_options() -> FileSearchOptions {
+    load_state_file(FILE_SEARCH_FILE).unwrap_or_default()
+}
+
+/// Remember the picker's search options
+#[tauri::command]
+pub fn save_file_search_options(options: FileSearchOptions) -> Result<(), String> {
+    save_state_file(FILE_SEARCH_FILE, &options)
+}
+
+/// Subsequence match score of `query` against `candidate`, or None if not
+/// every query character appears in order. Consecutive runs and matches at
+/// word boundaries score higher; longer candidates score slightly lower.
+fn fuzzy_score(query: &[char], candidate: &str, case_sensitive: bool) -> Option<i64> {
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_ascii_lowercase()
+        }
+    };
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 0i64;
+    let mut next = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for &q in query {
+        let q = fold(q);
+        let index = (next..chars.len()).find(|&i| fold(chars[i]) == q)?;
+
+        score += 1;
+        if previous_match.is_some_and(|p| p + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || matches!(chars[index - 1], '/' | '_' | '-' | '.' | ' ') {
+            score += 3;
+        }
+
+        previous_match = Some(index);
+        next = index + 1;
+    }
+
+    Some(score * 100 - chars.len() as i64)
+}
+
+/// Fuzzy-search project files server-side. Options not given fall back to
+/// the saved defaults.
+#[tauri::command]
+pub fn search_project_files(
+    root: String,
+    query: String,
+    options: Option<FileSearchOptions>,
+    limit: Option<usize>,
+    respect_gitignore: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let options = options.unwrap_or_else(get_file_search_options);
+    let root_path = PathBuf::from(&root);
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    let filter = FileFilter {
+        gitignored: if respect_gitignore.unwrap_or(true) {
+            gitignored_paths(&root)
+        } else {
+            None
+        },
+        ignored_dirs: ignored_dirs(),
+        include_hidden: options.include_hidden,
+    };
+    collect_files_recursive(&root_path, &root_path, &filter, &mut files, &mut visited)?;
+
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        files.truncate(limit);
+        return Ok(files);
+    }
+
+    let mut scored: Vec<(i64, String)> = files
+        .into_iter()
+        .filter_map(|file| {
+            let target = if options.match_path {
+                file.as_str()
+            } else {
+                file.rsplit('/').next().unwrap_or(&file)
+            };
+            let score = fuzzy_score(&query, target, options.case_sensitive)?;
+            Some((score, file))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    Ok(scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, file)| file)
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    path: String, // Relative to the project root
+    line_number: usize,
+    line_text: String,
+}
+
+// Matched lines longer than this are cut short in results
+const MAX_HIT_LINE_CHARS: usize = 500;
+
+/// Grep file contents across the project, skipping ignored directories and
+/// binary files. Stops as soon as `max_results` hits have been found.
+#[tauri::command]
+pub fn search_file_contents(
+    root: String,
+    query: String,
+    case_sensitive: bool,
+    max_results: usize,
+) -> Result<Vec<SearchHit>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let root_path = PathBuf::from(&root);
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    let filter = FileFilter {
+        gitignored: gitignored_paths(&root),
+        ignored_dirs: ignored_dirs(),
+        include_hidden: false,
+    };
+    collect_files_recursive(&root_path, &root_path, &filter, &mut files, &mut visited)?;
+
+    let needle = if case_sensitive {
+        query
+    } else {
+        query.to_lowercase()
+    };
+
+    let mut hits = Vec::new();
+    for rel_path in files {
+        if hits.len() >= max_results {
+            break;
+        }
+
+        let path = root_path.join(&rel_path);
+        if is_binary_file(&path).unwrap_or(true) {
+            continue;
+        }
+        let Ok(file) = fs::File::open(&path) else {
+            continue;
+        };
+
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        let mut line_number = 0;
+        while hits.len() < max_results {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => line_number += 1,
+            }
+
+            let line = String::from_utf8_lossy(&buf);
+            let matched = if case_sensitive {
+                line.contains(&needle)
+            } else {
+                line.to_lowercase().contains(&needle)
+            };
+            if matched {
+                hits.push(SearchHit {
+                    path: rel_path.clone(),
+                    line_number,
+                    line_text: line.trim_end().chars().take(MAX_HIT_LINE_CHARS).collect(),
+                });
+            }
+        }
+    }
+
+    Ok(hits)
+}
+
+/// Total size in bytes of all files under a directory (symlinks are not followed)
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(read_dir) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    read_dir
+        .filter_map(|e| e.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+// Stop counting after this many files (overridable via the
+// `directorySizeMaxFiles` config key)
+const DEFAULT_DIR_SIZE_MAX_FILES: u64 = 200_000;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirSize {
+    total_bytes: u64,
+    file_count: u64,
+    dir_count: u64,
+    partial: bool, // Stopped at the file-count cap
+}
+
+/// Recursive size of a directory for the explorer, skipping ignored
+/// directories. Only the top-level path is followed if it's a symlink, so
+/// links inside the tree can't cause cycles.
+#[tauri::command]
+pub async fn get_directory_size(path: String) -> Result<DirSize, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(expand_path(&path));
+        if !fs::metadata(&root).map_err(|e| e.to_string())?.is_dir() {
+            return Err(format!("Not a directory: {}", path));
+        }
+
+        let max_files = load_config()
+            .ok()
+            .and_then(|config| config.get("directorySizeMaxFiles")?.as_u64())
+            .unwrap_or(DEFAULT_DIR_SIZE_MAX_FILES);
+        let ignored = ignored_dirs();
+
+        let mut size = DirSize::default();
+        let mut pending = vec![root];
+        while let Some(dir) = pending.pop() {
+            let Ok(read_dir) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in read_dir.filter_map(|e| e.ok()) {
+                // DirEntry metadata doesn't follow symlinks
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                if meta.is_dir() {
+                    if !ignored.contains(entry.file_name().to_string_lossy().as_ref()) {
+                        size.dir_count += 1;
+                        pending.push(entry.path());
+                    }
+                    continue;
+                }
+                if size.file_count >= max_files {
+                    size.partial = true;
+                    return Ok(size);
+                }
+                size.file_count += 1;
+                size.total_bytes += meta.len();
+            }
+        }
+        Ok(size)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_targets_resolve_relative_and_flag_broken_links() {
+        let dir = std::env::temp_dir().join(format!("aterm-symlinks-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("file.txt"), "").unwrap();
+        std::os::unix::fs::symlink("../file.txt", dir.join("sub/link")).unwrap();
+        std::os::unix::fs::symlink("missing.txt", dir.join("broken")).unwrap();
+
+        let (raw, resolved) = symlink_targets(&dir.join("sub/link"));
+        assert_eq!(raw.as_deref(), Some("../file.txt"));
+        let expected = dir.join("file.txt").canonicalize().unwrap();
+        assert_eq!(resolved, Some(expected.to_string_lossy().to_string()));
+
+        let (raw, resolved) = symlink_targets(&dir.join("broken"));
+        assert_eq!(raw.as_deref(), Some("missing.txt"));
+        assert_eq!(resolved, None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
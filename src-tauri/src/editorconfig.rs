@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Effective .editorconfig settings for one file; None where no section set
+// the property (or it was `unset`)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorConfig {
+    pub indent_style: Option<String>, // "space" or "tab"
+    pub indent_size: Option<u32>,     // `indent_size = tab` resolves to tab_width
+    pub tab_width: Option<u32>,
+    pub end_of_line: Option<String>, // "lf", "crlf", or "cr"
+    pub charset: Option<String>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+struct Section {
+    glob: String,
+    properties: Vec<(String, String)>,
+}
+
+struct ConfigFile {
+    dir: PathBuf,
+    root: bool,
+    sections: Vec<Section>,
+}
+
+fn parse_config_file(path: &Path) -> Option<ConfigFile> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut root = false;
+    let mut sections: Vec<Section> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push(Section {
+                glob: glob.to_string(),
+                properties: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_string();
+        match sections.last_mut() {
+            Some(section) => section.properties.push((key, value)),
+            // Only `root` is meaningful before the first section
+            None if key == "root" => root = value.eq_ignore_ascii_case("true"),
+            None => {}
+        }
+    }
+
+    Some(ConfigFile {
+        dir: path.parent()?.to_path_buf(),
+        root,
+        sections,
+    })
+}
+
+/// Index of the `}` closing the brace at the start of `p`, if any
+fn closing_brace(p: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < p.len() {
+        match p[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split brace contents on top-level commas
+fn brace_alternatives(inner: &[char]) -> Vec<Vec<char>> {
+    let mut alternatives = vec![Vec::new()];
+    let mut depth = 0;
+    let mut i = 0;
+    while i < inner.len() {
+        let c = inner[i];
+        match c {
+            '\\' if i + 1 < inner.len() => {
+                alternatives.last_mut().unwrap().extend([c, inner[i + 1]]);
+                i += 2;
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(Vec::new());
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        alternatives.last_mut().unwrap().push(c);
+        i += 1;
+    }
+    alternatives
+}
+
+/// `{num1..num2}` bounds, if the brace contents are a numeric range
+fn numeric_range(inner: &[char]) -> Option<(i64, i64)> {
+    let inner: String = inner.iter().collect();
+    let (low, high) = inner.split_once("..")?;
+    Some((low.parse().ok()?, high.parse().ok()?))
+}
+
+/// Match a path against an EditorConfig glob: `*` (not crossing `/`), `**`,
+/// `?`, `[abc]`/`[!abc]`, `{a,b}`, `{1..10}`, and `\` escapes
+fn glob_match(p: &[char], t: &[char]) -> bool {
+    match p.first() {
+        None => t.is_empty(),
+        Some('*') if p.get(1) == Some(&'*') => (0..=t.len()).any(|i| glob_match(&p[2..], &t[i..])),
+        Some('*') => {
+            for i in 0..=t.len() {
+                if glob_match(&p[1..], &t[i..]) {
+                    return true;
+                }
+                if i < t.len() && t[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => t.first().is_some_and(|&c| c != '/') && glob_match(&p[1..], &t[1..]),
+        Some('[') => {
+            let Some(close) = p.iter().skip(2).position(|&c| c == ']').map(|i| i + 2) else {
+                return t.first() == Some(&'[') && glob_match(&p[1..], &t[1..]);
+            };
+            let Some(&c) = t.first().filter(|&&c| c != '/') else {
+                return false;
+            };
+            let (negated, class) = match p[1] {
+                '!' => (true, &p[2..close]),
+                _ => (false, &p[1..close]),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    matched |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    matched |= class[i] == c;
+                    i += 1;
+                }
+            }
+            matched != negated && glob_match(&p[close + 1..], &t[1..])
+        }
+        Some('{') => {
+            let Some(close) = closing_brace(p) else {
+                return t.first() == Some(&'{') && glob_match(&p[1..], &t[1..]);
+            };
+            let inner = &p[1..close];
+            let rest = &p[close + 1..];
+
+            if let Some((low, high)) = numeric_range(inner) {
+                let (low, high) = (low.min(high), low.max(high));
+                let sign = usize::from(t.first() == Some(&'-'));
+                let digits = t[sign..].iter().take_while(|c| c.is_ascii_digit()).count();
+                return (1..=digits).any(|len| {
+                    let number: String = t[..sign + len].iter().collect();
+                    number.parse::<i64>().is_ok_and(|n| low <= n && n <= high)
+                        && glob_match(rest, &t[sign + len..])
+                });
+            }
+
+            let alternatives = brace_alternatives(inner);
+            // A brace without a comma is matched literally
+            if alternatives.len() < 2 {
+                return t.first() == Some(&'{') && glob_match(&p[1..], &t[1..]);
+            }
+            alternatives.into_iter().any(|mut alternative| {
+                alternative.extend_from_slice(rest);
+                glob_match(&alternative, t)
+            })
+        }
+        Some('\\') if p.len() > 1 => t.first() == Some(&p[1]) && glob_match(&p[2..], &t[1..]),
+        Some(&c) => t.first() == Some(&c) && glob_match(&p[1..], &t[1..]),
+    }
+}
+
+/// Whether a section glob applies to `rel_path` (relative to the directory
+/// of the .editorconfig). Globs without a `/` match the file name at any depth.
+fn section_matches(glob: &str, rel_path: &str) -> bool {
+    let glob = match glob.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if glob.contains('/') => glob.to_string(),
+        None => format!("**/{}", glob),
+    };
+    let pattern: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = rel_path.chars().collect();
+    // `**/` also matches zero directories
+    glob_match(&pattern, &text)
+        || glob
+            .strip_prefix("**/")
+            .is_some_and(|top| glob_match(&top.chars().collect::<Vec<_>>(), &text))
+}
+
+/// Resolve the settings for `file`, reading .editorconfig files from its
+/// directory up to `root` (or the first one marked `root = true`)
+pub fn resolve_editorconfig(file: &Path, root: &Path) -> EditorConfig {
+    let mut files = Vec::new();
+    let mut dir = file.parent();
+    while let Some(current) = dir {
+        if let Some(config) = parse_config_file(&current.join(".editorconfig")) {
+            let is_root = config.root;
+            files.push(config);
+            if is_root {
+                break;
+            }
+        }
+        if current == root {
+            break;
+        }
+        dir = current.parent();
+    }
+
+    // Apply the outermost file first so closer files and later sections win
+    let mut properties: HashMap<String, String> = HashMap::new();
+    for config in files.iter().rev() {
+        let Ok(rel) = file.strip_prefix(&config.dir) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        for section in &config.sections {
+            if section_matches(&section.glob, &rel) {
+                for (key, value) in &section.properties {
+                    properties.insert(key.clone(), value.to_lowercase());
+                }
+            }
+        }
+    }
+    properties.retain(|_, value| value != "unset");
+
+    let number = |key: &str| properties.get(key).and_then(|v| v.parse::<u32>().ok());
+    let flag = |key: &str| match properties.get(key).map(String::as_str) {
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        _ => None,
+    };
+    let indent_style = properties.get("indent_style").cloned();
+    let tab_width = number("tab_width");
+    let indent_size = match properties.get("indent_size").map(String::as_str) {
+        Some("tab") => tab_width,
+        // Tabs with no explicit size indent by one tab width
+        None if indent_style.as_deref() == Some("tab") => tab_width,
+        _ => number("indent_size"),
+    };
+
+    EditorConfig {
+        indent_style,
+        indent_size,
+        tab_width: tab_width.or(indent_size),
+        end_of_line: properties.get("end_of_line").cloned(),
+        charset: properties.get("charset").cloned(),
+        trim_trailing_whitespace: flag("trim_trailing_whitespace"),
+        insert_final_newline: flag("insert_final_newline"),
+    }
+}
+
+/// Effective .editorconfig settings for `file` (absolute, or relative to the
+/// project at `path`)
+#[tauri::command]
+pub fn get_editorconfig(path: String, file: String) -> Result<EditorConfig, String> {
+    let root = PathBuf::from(&path);
+    let file = root.join(&file);
+    if file.is_dir() {
+        return Err(format!("Not a file: {}", file.display()));
+    }
+    Ok(resolve_editorconfig(&file, &root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn strings(alternatives: Vec<Vec<char>>) -> Vec<String> {
+        alternatives
+            .into_iter()
+            .map(|a| a.into_iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn glob_match_table() {
+        let cases = [
+            ("*", "main.rs", true),
+            ("*.rs", "main.rs", true),
+            ("*.rs", "src/main.rs", false),
+            ("**.rs", "src/main.rs", true),
+            ("src/**/*.rs", "src/a/b/main.rs", true),
+            ("?.md", "a.md", true),
+            ("?.md", "ab.md", false),
+            ("?", "/", false),
+            ("*.{js,ts}", "app.ts", true),
+            ("*.{js,ts}", "app.rs", false),
+            ("{a,{b,c}}.txt", "c.txt", true),
+            ("{single}", "{single}", true),
+            ("file{1..3}", "file2", true),
+            ("file{1..3}", "file4", false),
+            ("file{3..1}", "file1", true),
+            ("v{-2..2}", "v-1", true),
+            ("[abc].txt", "b.txt", true),
+            ("[!abc].txt", "b.txt", false),
+            ("[a-c].txt", "c.txt", true),
+            ("[a-c].txt", "d.txt", false),
+            ("[a-c]", "/", false),
+            ("\\*.txt", "*.txt", true),
+            ("\\*.txt", "a.txt", false),
+        ];
+        for (glob, path, expected) in cases {
+            assert_eq!(
+                glob_match(&chars(glob), &chars(path)),
+                expected,
+                "{} vs {}",
+                glob,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn section_matches_table() {
+        let cases = [
+            ("*.rs", "main.rs", true),
+            ("*.rs", "src/deep/main.rs", true),
+            ("Makefile", "sub/Makefile", true),
+            ("/Makefile", "Makefile", true),
+            ("/Makefile", "sub/Makefile", false),
+            ("src/*.rs", "src/main.rs", true),
+            ("src/*.rs", "other/src/main.rs", false),
+            ("**/test/*.js", "test/a.js", true),
+            ("lib/**.py", "lib/a/b.py", true),
+        ];
+        for (glob, path, expected) in cases {
+            assert_eq!(
+                section_matches(glob, path),
+                expected,
+                "{} vs {}",
+                glob,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn brace_alternatives_split_top_level() {
+        assert_eq!(strings(brace_alternatives(&chars("a,b"))), ["a", "b"]);
+        assert_eq!(
+            strings(brace_alternatives(&chars("a,{b,c}"))),
+            ["a", "{b,c}"]
+        );
+        assert_eq!(
+            strings(brace_alternatives(&chars("a\\,b,c"))),
+            ["a\\,b", "c"]
+        );
+        assert_eq!(strings(brace_alternatives(&chars("only"))), ["only"]);
+    }
+
+    #[test]
+    fn numeric_range_bounds() {
+        assert_eq!(numeric_range(&chars("1..3")), Some((1, 3)));
+        assert_eq!(numeric_range(&chars("-5..10")), Some((-5, 10)));
+        assert_eq!(numeric_range(&chars("a..3")), None);
+        assert_eq!(numeric_range(&chars("1,3")), None);
+    }
+}
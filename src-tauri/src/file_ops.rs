@@ -1,4 +1,73 @@
+use crate::config::load_config;
+use crate::directory::expand_path;
+use crate::editorconfig::{resolve_editorconfig, EditorConfig};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+
+// Files larger than this prompt before opening in the editor (overridable via
+// the `editorMaxFileSize` config key, in bytes)
+const DEFAULT_EDITOR_MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+// How much of a file to sniff when checking for binary content
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+// Number of lines returned for text file previews
+const PREVIEW_TEXT_LINES: usize = 200;
+
+// Max width/height of thumbnails generated for images over the preview cap
+const PREVIEW_THUMBNAIL_SIZE: u32 = 512;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePreview {
+    kind: String, // "image", "text", or "binary"
+    mime_type: Option<String>,
+    content: String, // base64 for images, plain text for text files
+    truncated: bool,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CappedContent {
+    content: String,
+    truncated: bool, // Content stops short of the end of the file
+    size: u64,       // Total file size in bytes
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenCheck {
+    ok: bool,
+    reason: Option<String>, // "tooLarge" or "binary"
+    size: u64,
+}
+
+/// Detect binary content by looking for NUL bytes near the start of the file
+pub fn is_binary_file(path: &Path) -> std::io::Result<bool> {
+    let mut file = fs::File::open(path)?;
+    let mut buf = [0u8; BINARY_SNIFF_BYTES];
+    let n = file.read(&mut buf)?;
+    Ok(buf[..n].contains(&0))
+}
+
+/// Image MIME type for a path based on its extension, if it's a known image
+pub fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "bmp" => Some("image/bmp"),
+        "ico" => Some("image/x-icon"),
+        "svg" => Some("image/svg+xml"),
+        _ => None,
+    }
+}
 
 #[tauri::command]
 pub fn open_in_editor(path: String, editor: Option<String>) -> Result<(), String> {
@@ -7,6 +76,10 @@ pub fn open_in_editor(path: String, editor: Option<String>) -> Result<(), String
     let result = match editor.as_str() {
         "vscode" | "code" => std::process::Command::new("code").arg(&path).spawn(),
         "cursor" => std::process::Command::new("cursor").arg(&path).spawn(),
+        // A path to an editor binary, e.g. ~/bin/subl
+        custom if custom.contains('/') => std::process::Command::new(expand_path(custom))
+            .arg(&path)
+            .spawn(),
         _ => {
             // Use system default - 'open' on macOS
             #[cfg(target_os = "macos")]
@@ -31,7 +104,302 @@ pub fn read_file_content(path: String) -> Result<String, String> {
     fs::read_to_string(&path).map_err(|e| e.to_string())
 }
 
+/// Read at most `max_bytes` of a text file for the editor. A cap landing
+/// mid-character is moved back to the previous character boundary.
+#[tauri::command]
+pub fn read_file_content_capped(path: String, max_bytes: u64) -> Result<CappedContent, String> {
+    let file_path = Path::new(&path);
+    let size = fs::metadata(file_path).map_err(|e| e.to_string())?.len();
+    if is_binary_file(file_path).map_err(|e| e.to_string())? {
+        return Err(format!("Binary file: {}", path));
+    }
+
+    let file = fs::File::open(file_path).map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.take(max_bytes)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    let truncated = (bytes.len() as u64) < size;
+
+    let content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        // Only an incomplete sequence at the cut is expected; anything
+        // else is invalid UTF-8, as read_file_content would report
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).map_err(|e| e.to_string())?
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    Ok(CappedContent {
+        content,
+        truncated,
+        size,
+    })
+}
+
+/// Strip trailing spaces/tabs from every line and/or make sure non-empty
+/// content ends with a newline, keeping the file's line endings
+fn apply_whitespace_rules(content: String, trim: bool, final_newline: bool) -> String {
+    let mut content = if trim {
+        content
+            .split_inclusive('\n')
+            .map(|line| {
+                let (text, ending) = match line.strip_suffix("\r\n") {
+                    Some(text) => (text, "\r\n"),
+                    None => match line.strip_suffix('\n') {
+                        Some(text) => (text, "\n"),
+                        None => (line, ""),
+                    },
+                };
+                format!("{}{}", text.trim_end_matches([' ', '\t']), ending)
+            })
+            .collect()
+    } else {
+        content
+    };
+
+    if final_newline && !content.is_empty() && !content.ends_with('\n') {
+        let ending = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        content.push_str(ending);
+    }
+    content
+}
+
+/// Save a file, optionally trimming trailing whitespace and adding a final
+/// newline. Each rule is taken from its parameter, or from the file's
+/// .editorconfig when `use_editorconfig` is set; both are off by default.
 #[tauri::command]
-pub fn write_file_content(path: String, content: String) -> Result<(), String> {
+pub fn write_file_content(
+    path: String,
+    content: String,
+    trim_trailing_whitespace: Option<bool>,
+    insert_final_newline: Option<bool>,
+    use_editorconfig: Option<bool>,
+) -> Result<(), String> {
+    let editorconfig = if use_editorconfig.unwrap_or(false) {
+        // No project root here, so look up to the filesystem root
+        resolve_editorconfig(Path::new(&path), Path::new("/"))
+    } else {
+        EditorConfig::default()
+    };
+    let trim = trim_trailing_whitespace
+        .or(editorconfig.trim_trailing_whitespace)
+        .unwrap_or(false);
+    let final_newline = insert_final_newline
+        .or(editorconfig.insert_final_newline)
+        .unwrap_or(false);
+
+    let content = apply_whitespace_rules(content, trim, final_newline);
     fs::write(&path, content).map_err(|e| e.to_string())
 }
+
+/// Fail with a clear error when the parent directory of `path` is missing
+fn ensure_parent_exists(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => Err(format!(
+            "Parent directory does not exist: {}",
+            parent.display()
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn create_file(path: String) -> Result<(), String> {
+    let path = Path::new(&path);
+    ensure_parent_exists(path)?;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map(|_| ())
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => format!("{} already exists", path.display()),
+            _ => e.to_string(),
+        })
+}
+
+#[tauri::command]
+pub fn create_directory(path: String) -> Result<(), String> {
+    let path = Path::new(&path);
+    ensure_parent_exists(path)?;
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    fs::create_dir(path).map_err(|e| e.to_string())
+}
+
+/// Rename or move a file or directory, never overwriting the destination
+#[tauri::command]
+pub fn rename_path(from: String, to: String) -> Result<(), String> {
+    let (from, to) = (Path::new(&from), Path::new(&to));
+    if fs::symlink_metadata(from).is_err() {
+        return Err(format!("{} does not exist", from.display()));
+    }
+    ensure_parent_exists(to)?;
+    if fs::symlink_metadata(to).is_ok() {
+        return Err(format!("{} already exists", to.display()));
+    }
+    fs::rename(from, to).map_err(|e| e.to_string())
+}
+
+/// Delete a file, symlink, or directory. Non-empty directories are only
+/// removed when `recursive` is set.
+#[tauri::command]
+pub fn delete_path(path: String, recursive: bool) -> Result<(), String> {
+    let path = Path::new(&path);
+    let metadata =
+        fs::symlink_metadata(path).map_err(|_| format!("{} does not exist", path.display()))?;
+
+    if !metadata.is_dir() {
+        return fs::remove_file(path).map_err(|e| e.to_string());
+    }
+
+    let is_empty = fs::read_dir(path)
+        .map_err(|e| e.to_string())?
+        .next()
+        .is_none();
+    if is_empty {
+        fs::remove_dir(path).map_err(|e| e.to_string())
+    } else if recursive {
+        fs::remove_dir_all(path).map_err(|e| e.to_string())
+    } else {
+        Err(format!("{} is not empty", path.display()))
+    }
+}
+
+/// Pre-check before loading a file into the editor, so huge or binary files
+/// can be confirmed by the user instead of freezing the UI
+#[tauri::command]
+pub fn should_open_in_editor(path: String) -> Result<OpenCheck, String> {
+    let file_path = Path::new(&path);
+    let size = fs::metadata(file_path).map_err(|e| e.to_string())?.len();
+
+    let max_size = load_config()
+        .ok()
+        .and_then(|config| config.get("editorMaxFileSize")?.as_u64())
+        .unwrap_or(DEFAULT_EDITOR_MAX_FILE_SIZE);
+
+    let reason = if size > max_size {
+        Some("tooLarge")
+    } else if is_binary_file(file_path).map_err(|e| e.to_string())? {
+        Some("binary")
+    } else {
+        None
+    };
+
+    Ok(OpenCheck {
+        ok: reason.is_none(),
+        reason: reason.map(|r| r.to_string()),
+        size,
+    })
+}
+
+fn preview_image(
+    path: &Path,
+    mime: &str,
+    size: u64,
+    max_bytes: u64,
+) -> Result<FilePreview, String> {
+    if size <= max_bytes {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        return Ok(FilePreview {
+            kind: "image".to_string(),
+            mime_type: Some(mime.to_string()),
+            content: BASE64.encode(bytes),
+            truncated: false,
+            size,
+        });
+    }
+
+    // Too large to send as-is: downscale raster images to a PNG thumbnail
+    let thumbnail = image::open(path).ok().and_then(|img| {
+        let mut png = Vec::new();
+        img.thumbnail(PREVIEW_THUMBNAIL_SIZE, PREVIEW_THUMBNAIL_SIZE)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .ok()?;
+        Some(png)
+    });
+
+    Ok(match thumbnail {
+        Some(png) => FilePreview {
+            kind: "image".to_string(),
+            mime_type: Some("image/png".to_string()),
+            content: BASE64.encode(png),
+            truncated: true,
+            size,
+        },
+        None => FilePreview {
+            kind: "image".to_string(),
+            mime_type: Some(mime.to_string()),
+            content: String::new(),
+            truncated: true,
+            size,
+        },
+    })
+}
+
+fn preview_text(path: &Path, size: u64, max_bytes: u64) -> Result<FilePreview, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file.take(max_bytes));
+    let mut content = String::new();
+    let mut lines = 0;
+
+    let mut read = 0u64;
+
+    // Read raw lines so a byte cap landing mid-character doesn't fail decoding
+    loop {
+        let mut line = Vec::new();
+        let n = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| e.to_string())?;
+        if n == 0 || lines == PREVIEW_TEXT_LINES {
+            break;
+        }
+        content.push_str(&String::from_utf8_lossy(&line));
+        read += n as u64;
+        lines += 1;
+    }
+
+    let truncated = read < size;
+    Ok(FilePreview {
+        kind: "text".to_string(),
+        mime_type: None,
+        content,
+        truncated,
+        size,
+    })
+}
+
+/// Lightweight preview for the explorer: base64 image data (or a thumbnail
+/// for large images), the first lines of text files, or just metadata for
+/// other binary files
+#[tauri::command]
+pub fn get_file_preview(path: String, max_bytes: u64) -> Result<FilePreview, String> {
+    let file_path = Path::new(&path);
+    let size = fs::metadata(file_path).map_err(|e| e.to_string())?.len();
+
+    if let Some(mime) = image_mime_type(file_path) {
+        return preview_image(file_path, mime, size, max_bytes);
+    }
+
+    if is_binary_file(file_path).map_err(|e| e.to_string())? {
+        return Ok(FilePreview {
+            kind: "binary".to_string(),
+            mime_type: None,
+            content: String::new(),
+            truncated: false,
+            size,
+        });
+    }
+
+    preview_text(file_path, size, max_bytes)
+}
@@ -1,7 +1,14 @@
+use crate::directory::dir_size;
+use crate::git_timing::TimedCommand;
+use crate::worktree::{hash_color, recorded_base_ref};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -46,6 +53,247 @@ pub struct CommitFile {
     deletions: i32,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlameLine {
+    line_number: usize,
+    commit_hash: String,
+    author: String,
+    timestamp: i64,
+    summary: String,
+    committed: bool, // False for lines with uncommitted changes
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitAttributes {
+    text: Option<String>,
+    binary: bool,
+    diff: Option<String>,
+    merge: Option<String>,
+    linguist_generated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GutterMarker {
+    line: u32,
+    kind: String, // "added", "modified", or "removed" (lines deleted above `line`)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcResult {
+    size_before: u64,
+    size_after: u64,
+    bytes_reclaimed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GcProgress {
+    path: String,
+    message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CloneProgress {
+    destination: String,
+    message: String,
+    percent: Option<u32>, // Progress of the current phase, e.g. "Receiving objects"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfUpdateStatus {
+    is_git_checkout: bool,
+    behind: i32,
+    latest_subject: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    kind: String, // "context", "added", or "removed"
+    content: String,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+    whitespace_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    header: String,
+    old_start: u32,
+    old_count: u32,
+    new_start: u32,
+    new_count: u32,
+    lines: Vec<DiffLine>,
+    whitespace_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedRef {
+    full_sha: String,
+    short_sha: String,
+    kind: String, // "branch", "tag", "remote", or "commit"
+    full_name: Option<String>,
+    annotated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefCheck {
+    at_ref: bool, // HEAD is at the ref and the working tree is clean
+    head_matches: bool,
+    clean: bool,
+    head_sha: Option<String>, // None on an unborn branch
+    ref_sha: String,
+    changed_files: usize, // Staged, unstaged, and untracked paths
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffSide {
+    diff: String,
+    is_binary: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffs {
+    staged: FileDiffSide,
+    unstaged: FileDiffSide,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchedRef {
+    name: String,   // Local ref that was updated, e.g. origin/main
+    status: String, // "new", "updated", "forced", "tag", or "rejected"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchResult {
+    updated: Vec<FetchedRef>,
+    pruned: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StashEntry {
+    index: usize,
+    branch: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCounts {
+    staged: u32,
+    modified: u32, // Unstaged changes to tracked files
+    untracked: u32,
+    conflicted: u32,
+    ahead: u32,
+    behind: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteInfo {
+    name: String,
+    fetch_url: String,
+    push_url: String, // Same as fetch_url unless a push URL is configured
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagInfo {
+    name: String,
+    commit_hash: String, // Commit the tag points at (peeled for annotated tags)
+    message: Option<String>, // Subject of the tag message; None for lightweight tags
+    is_annotated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorInfo {
+    name: String,
+    email: String,
+    commit_count: u32,
+    color: String,
+    initials: String,
+}
+
+// Running clones by operation id, so they can be cancelled
+pub type CloneMap = Arc<Mutex<HashMap<String, CloneOperation>>>;
+
+pub struct CloneOperation {
+    child: std::process::Child,
+    destination: String,
+    created_destination: bool, // False if git cloned into an existing empty dir
+}
+
+// Author lists per repo path, keyed by the HEAD sha they were computed at
+pub type AuthorCache = Arc<Mutex<HashMap<String, (String, Vec<AuthorInfo>)>>>;
+
+// How far back get_author_info looks
+const AUTHOR_HISTORY_LIMIT: &str = "--max-count=1000";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchDiff {
+    base_ref: String,
+    merge_base: String,
+    commit_count: u32,
+    files: Vec<CommitFile>,
+    additions: i32,
+    deletions: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IgnoreCheck {
+    ignored: bool,
+    source: Option<String>, // e.g. ".gitignore" or ".git/info/exclude"
+    line: Option<u32>,
+    pattern: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    ref_name: String,
+    sha: String,
+    label: String,
+    timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteProvider {
+    provider: String, // "github", "gitlab", "bitbucket", or "self-hosted"
+    host: String,
+    owner: String,
+    repo: String,
+    web_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrecommitSummary {
+    has_staged: bool,
+    has_unstaged: bool,
+    has_untracked: bool,
+    has_conflicts: bool,
+    identity_configured: bool,  // user.name and user.email are both set
+    repo_state: Option<String>, // "merge", "rebase", "cherry-pick", "revert", or "bisect"
+}
+
 fn parse_status_code(code: &str) -> &'static str {
     match code {
         "M" => "modified",
@@ -85,7 +333,7 @@ pub fn get_git_status(path: String) -> Result<GitStatus, String> {
     // Get current branch
     let branch_output = std::process::Command::new("git")
         .args(["-C", &path, "branch", "--show-current"])
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
     let branch = String::from_utf8_lossy(&branch_output.stdout)
         .trim()
@@ -103,7 +351,7 @@ pub fn get_git_status(path: String) -> Result<GitStatus, String> {
             "--count",
             "@{upstream}...HEAD",
         ])
-        .output();
+        .timed_output();
 
     if let Ok(output) = revlist_output {
         if output.status.success() {
@@ -119,7 +367,7 @@ pub fn get_git_status(path: String) -> Result<GitStatus, String> {
     // Get status with porcelain v1
     let status_output = std::process::Command::new("git")
         .args(["-C", &path, "status", "--porcelain=v1"])
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     let status_text = String::from_utf8_lossy(&status_output.stdout);
@@ -187,265 +435,439 @@ pub fn get_git_status(path: String) -> Result<GitStatus, String> {
     })
 }
 
-#[tauri::command]
-pub fn get_file_diff(path: String, file: String, staged: bool) -> Result<String, String> {
-    let mut args = vec!["-C", &path, "diff"];
-    if staged {
-        args.push("--staged");
-    }
-    args.push("--");
-    args.push(&file);
-
-    let output = std::process::Command::new("git")
-        .args(&args)
-        .output()
-        .map_err(|e| e.to_string())?;
-
-    // If no diff (e.g., untracked file), show the file content
-    if output.stdout.is_empty() {
-        let file_path = PathBuf::from(&path).join(&file);
-        if file_path.exists() {
-            let content = fs::read_to_string(&file_path).unwrap_or_default();
-            // Format as a pseudo-diff for new files
-            let lines: Vec<String> = content.lines().map(|l| format!("+{}", l)).collect();
-            return Ok(format!("New file: {}\n\n{}", file, lines.join("\n")));
+/// Tally `git status` into counts with a single call, without building
+/// per-file entries. Also returns what HEAD is: the branch name, or the
+/// short sha when detached (neither in a repo with no commits).
+fn tally_status(path: &str) -> Result<(StatusCounts, Option<String>), String> {
+    let status = git_stdout(
+        path,
+        &[
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "--untracked-files=normal",
+        ],
+    )?;
+
+    let mut counts = StatusCounts::default();
+    let mut branch = None;
+    let mut oid = None;
+    for line in status.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            branch = Some(head).filter(|h| *h != "(detached)");
+        } else if let Some(commit) = line.strip_prefix("# branch.oid ") {
+            oid = commit.get(..7).filter(|_| commit != "(initial)");
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            // "+<ahead> -<behind>", only present with an upstream
+            let mut parts = ab.split_whitespace();
+            counts.ahead = parts
+                .next()
+                .and_then(|a| a.trim_start_matches('+').parse().ok())
+                .unwrap_or(0);
+            counts.behind = parts
+                .next()
+                .and_then(|b| b.trim_start_matches('-').parse().ok())
+                .unwrap_or(0);
+        } else if line.starts_with("? ") {
+            counts.untracked += 1;
+        } else if line.starts_with("u ") {
+            counts.conflicted += 1;
+        } else if line.starts_with("1 ") || line.starts_with("2 ") {
+            let xy = line.as_bytes();
+            counts.staged += u32::from(xy.get(2) != Some(&b'.'));
+            counts.modified += u32::from(xy.get(3) != Some(&b'.'));
         }
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok((counts, branch.or(oid).map(String::from)))
 }
 
+/// Badge counts (staged, modified, untracked, conflicted) plus ahead/behind,
+/// cheap enough to poll across many repos. Use `get_git_status` for the
+/// file lists.
 #[tauri::command]
-pub fn stage_files(path: String, files: Vec<String>) -> Result<(), String> {
-    let mut args = vec!["-C".to_string(), path, "add".to_string(), "--".to_string()];
-    args.extend(files);
-
-    let output = std::process::Command::new("git")
-        .args(&args)
-        .output()
-        .map_err(|e| e.to_string())?;
+pub fn get_status_counts(path: String) -> Result<StatusCounts, String> {
+    tally_status(&path).map(|(counts, _)| counts)
+}
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// One-line summary for window titles, e.g. "myrepo · feature-x · +3 ~1 ?2"
+/// (staged, modified, untracked; zero counts are left out). Uses a single
+/// `git status` call since titles refresh often.
+#[tauri::command]
+pub fn get_repo_title_summary(path: String) -> Result<String, String> {
+    let (counts, head) = tally_status(&path)?;
+
+    let name = Path::new(&path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    let mut parts = vec![name];
+    parts.extend(head);
+    let modified = counts.modified + counts.conflicted;
+    let counts: Vec<String> = [
+        ("+", counts.staged),
+        ("~", modified),
+        ("?", counts.untracked),
+    ]
+    .into_iter()
+    .filter(|(_, count)| *count > 0)
+    .map(|(sign, count)| format!("{}{}", sign, count))
+    .collect();
+    if !counts.is_empty() {
+        parts.push(counts.join(" "));
     }
+    Ok(parts.join(" · "))
 }
 
-#[tauri::command]
-pub fn stage_all(path: String) -> Result<(), String> {
+fn read_git_attributes(path: &str, file: &str) -> Result<GitAttributes, String> {
     let output = std::process::Command::new("git")
-        .args(["-C", &path, "add", "-A"])
-        .output()
+        .args(["-C", path, "check-attr", "-a", "--", file])
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let mut attrs = GitAttributes::default();
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    // Each line looks like "<file>: <attribute>: <value>" where value is
+    // "set", "unset", or an explicit value
+    for line in text.lines() {
+        let mut parts = line.rsplitn(3, ": ");
+        let (Some(value), Some(name)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let value = value.trim().to_string();
+
+        match name {
+            "text" => attrs.text = Some(value),
+            "binary" => attrs.binary = value == "set",
+            "diff" => attrs.diff = Some(value),
+            "merge" => attrs.merge = Some(value),
+            "linguist-generated" => attrs.linguist_generated = value != "unset" && value != "false",
+            _ => {}
+        }
     }
+
+    // "-diff" makes git treat the file as binary when diffing
+    if attrs.diff.as_deref() == Some("unset") {
+        attrs.binary = true;
+    }
+
+    Ok(attrs)
 }
 
 #[tauri::command]
-pub fn unstage_files(path: String, files: Vec<String>) -> Result<(), String> {
-    let mut args = vec![
-        "-C".to_string(),
-        path,
-        "reset".to_string(),
-        "HEAD".to_string(),
-        "--".to_string(),
-    ];
-    args.extend(files);
+pub fn get_git_attributes(path: String, file: String) -> Result<GitAttributes, String> {
+    read_git_attributes(&path, &file)
+}
+
+fn run_file_diff(
+    path: &str,
+    file: &str,
+    staged: bool,
+    ignore_whitespace: bool,
+    context_lines: Option<u32>,
+    word_diff: bool,
+) -> Result<Vec<u8>, String> {
+    let context = context_lines.map(|n| format!("-U{}", n));
+    let mut args = vec!["-C", path, "diff", "--no-color"];
+    if staged {
+        args.push("--staged");
+    }
+    if ignore_whitespace {
+        args.push("--ignore-all-space");
+    }
+    if let Some(context) = &context {
+        args.push(context);
+    }
+    if word_diff {
+        args.push("--word-diff=porcelain");
+    }
+    args.push("--");
+    args.push(file);
 
     let output = std::process::Command::new("git")
         .args(&args)
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+    Ok(output.stdout)
 }
 
-#[tauri::command]
-pub fn unstage_all(path: String) -> Result<(), String> {
-    let output = std::process::Command::new("git")
-        .args(["-C", &path, "reset", "HEAD"])
-        .output()
-        .map_err(|e| e.to_string())?;
+fn is_tracked(path: &str, file: &str) -> bool {
+    std::process::Command::new("git")
+        .args(["-C", path, "ls-files", "--error-unmatch", "--", file])
+        .timed_output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
 
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// Content of a new (untracked) file for display as a pseudo-diff, or a
+/// placeholder when .gitattributes marks it binary or generated
+fn read_new_file_lines(path: &str, file: &str) -> Result<Vec<String>, &'static str> {
+    // Respect .gitattributes declarations before reading the content
+    let attrs = read_git_attributes(path, file).unwrap_or_default();
+    if attrs.binary {
+        return Err("Binary file not shown");
+    }
+    if attrs.linguist_generated {
+        return Err("Generated file not shown");
     }
+
+    let content = fs::read_to_string(PathBuf::from(path).join(file)).unwrap_or_default();
+    Ok(content.lines().map(|l| l.to_string()).collect())
 }
 
 #[tauri::command]
-pub fn discard_changes(path: String, file: String, is_untracked: bool) -> Result<(), String> {
-    if is_untracked {
-        // Delete untracked file
-        let file_path = PathBuf::from(&path).join(&file);
-        fs::remove_file(&file_path).map_err(|e| e.to_string())?;
-    } else {
-        // Restore tracked file
-        let output = std::process::Command::new("git")
-            .args(["-C", &path, "checkout", "--", &file])
-            .output()
-            .map_err(|e| e.to_string())?;
+pub fn get_file_diff(
+    path: String,
+    file: String,
+    staged: bool,
+    ignore_whitespace: Option<bool>,
+    context_lines: Option<u32>,
+    word_diff: Option<bool>,
+) -> Result<String, String> {
+    let ignore_whitespace = ignore_whitespace.unwrap_or(false);
+    // Word diffs stay in git's porcelain format for intra-line highlighting
+    let stdout = run_file_diff(
+        &path,
+        &file,
+        staged,
+        ignore_whitespace,
+        context_lines,
+        word_diff.unwrap_or(false),
+    )?;
 
-        if !output.status.success() {
-            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    // If no diff (e.g., untracked file), show the file content
+    if stdout.is_empty() {
+        let file_path = PathBuf::from(&path).join(&file);
+        if file_path.exists() && !(ignore_whitespace && is_tracked(&path, &file)) {
+            return Ok(match read_new_file_lines(&path, &file) {
+                // Format as a pseudo-diff for new files
+                Ok(lines) => {
+                    let lines: Vec<String> = lines.iter().map(|l| format!("+{}", l)).collect();
+                    format!("New file: {}\n\n{}", file, lines.join("\n"))
+                }
+                Err(placeholder) => format!("New file: {}\n\n{}", file, placeholder),
+            });
         }
     }
-    Ok(())
+
+    Ok(String::from_utf8_lossy(&stdout).to_string())
 }
 
-#[tauri::command]
-pub fn git_commit(path: String, message: String) -> Result<String, String> {
-    let output = std::process::Command::new("git")
-        .args(["-C", &path, "commit", "-m", &message])
-        .output()
-        .map_err(|e| e.to_string())?;
+fn diff_side(diff: String) -> FileDiffSide {
+    let is_binary = diff
+        .lines()
+        .any(|l| l.starts_with("Binary files ") || l == "Binary file not shown");
+    FileDiffSide { diff, is_binary }
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+/// Both the staged and unstaged diff of a file in one round trip, so the
+/// diff view can toggle between them without re-invoking git
+#[tauri::command]
+pub fn get_file_diffs_both(path: String, file: String) -> Result<FileDiffs, String> {
+    let staged = run_file_diff(&path, &file, true, false, None, false)?;
+    // Untracked files only have a working tree side, shown as all added. A
+    // tracked file whose changes are all staged has an empty one.
+    let unstaged = if is_tracked(&path, &file) {
+        let unstaged = run_file_diff(&path, &file, false, false, None, false)?;
+        String::from_utf8_lossy(&unstaged).to_string()
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+        get_file_diff(path, file, false, None, None, None)?
+    };
+
+    Ok(FileDiffs {
+        staged: diff_side(String::from_utf8_lossy(&staged).to_string()),
+        unstaged: diff_side(unstaged),
+    })
 }
 
-#[tauri::command]
-pub fn git_push(path: String) -> Result<String, String> {
-    // First try normal push
-    let output = std::process::Command::new("git")
-        .args(["-C", &path, "push"])
-        .output()
-        .map_err(|e| e.to_string())?;
+/// Tag removed/added runs whose content is identical once all whitespace is
+/// removed, pairing lines one-to-one when the whole run doesn't match
+fn mark_whitespace_only(lines: &mut [DiffLine]) {
+    let squash = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
 
-    if output.status.success() {
-        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
-    }
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].kind == "context" {
+            i += 1;
+            continue;
+        }
 
-    // If that fails, try to set upstream
-    let branch_output = std::process::Command::new("git")
-        .args(["-C", &path, "branch", "--show-current"])
-        .output()
-        .map_err(|e| e.to_string())?;
-    let branch = String::from_utf8_lossy(&branch_output.stdout)
-        .trim()
-        .to_string();
+        let start = i;
+        while i < lines.len() && lines[i].kind == "removed" {
+            i += 1;
+        }
+        let mid = i;
+        while i < lines.len() && lines[i].kind == "added" {
+            i += 1;
+        }
 
-    let output = std::process::Command::new("git")
-        .args(["-C", &path, "push", "-u", "origin", &branch])
-        .output()
-        .map_err(|e| e.to_string())?;
+        let (removed, added) = lines[start..i].split_at_mut(mid - start);
+        let joined = |run: &[DiffLine]| run.iter().map(|l| squash(&l.content)).collect::<String>();
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        if joined(removed) == joined(added) {
+            removed.iter_mut().for_each(|l| l.whitespace_only = true);
+            added.iter_mut().for_each(|l| l.whitespace_only = true);
+        } else {
+            for (old, new) in removed.iter_mut().zip(added.iter_mut()) {
+                if squash(&old.content) == squash(&new.content) {
+                    old.whitespace_only = true;
+                    new.whitespace_only = true;
+                }
+            }
+        }
     }
 }
 
-#[tauri::command]
-pub fn get_commit_history(path: String, limit: i32) -> Result<Vec<CommitSummary>, String> {
-    // Get commit info with custom format
-    let format = "%H|%h|%s|%an|%ct";
-    let output = std::process::Command::new("git")
-        .args([
-            "-C",
-            &path,
-            "log",
-            &format!("--format={}", format),
-            &format!("-n{}", limit),
-        ])
-        .output()
-        .map_err(|e| e.to_string())?;
+fn parse_unified_diff(text: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut old_line = 0;
+    let mut new_line = 0;
+
+    for line in text.lines() {
+        if let Some((old_start, old_count, new_start, new_count)) = parse_hunk_header(line) {
+            hunks.push(DiffHunk {
+                header: line.to_string(),
+                old_start,
+                old_count,
+                new_start,
+                new_count,
+                lines: Vec::new(),
+                whitespace_only: false,
+            });
+            old_line = old_start;
+            new_line = new_start;
+            continue;
+        }
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        let Some(hunk) = hunks.last_mut() else {
+            continue; // File header lines before the first hunk
+        };
+
+        let (kind, content) = match line.chars().next() {
+            Some('+') => ("added", &line[1..]),
+            Some('-') => ("removed", &line[1..]),
+            Some(' ') => ("context", &line[1..]),
+            _ => continue, // "\ No newline at end of file"
+        };
+
+        let (old, new) = match kind {
+            "added" => (None, Some(new_line)),
+            "removed" => (Some(old_line), None),
+            _ => (Some(old_line), Some(new_line)),
+        };
+        if old.is_some() {
+            old_line += 1;
+        }
+        if new.is_some() {
+            new_line += 1;
+        }
+
+        hunk.lines.push(DiffLine {
+            kind: kind.to_string(),
+            content: content.to_string(),
+            old_line: old,
+            new_line: new,
+            whitespace_only: false,
+        });
     }
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
+    for hunk in &mut hunks {
+        mark_whitespace_only(&mut hunk.lines);
+        let mut changed = hunk.lines.iter().filter(|l| l.kind != "context").peekable();
+        hunk.whitespace_only = changed.peek().is_some() && changed.all(|l| l.whitespace_only);
+    }
 
-    let mut commits = Vec::new();
-    let log_text = String::from_utf8_lossy(&output.stdout);
+    hunks
+}
 
-    for line in log_text.lines() {
-        let parts: Vec<&str> = line.split('|').collect();
-        if parts.len() >= 5 {
-            let timestamp: i64 = parts[4].parse().unwrap_or(0);
-            let seconds_ago = now - timestamp;
+/// Diff for a file parsed into hunks and lines, with whitespace-only changes
+/// tagged so the UI can de-emphasize them
+#[tauri::command]
+pub fn get_file_diff_structured(
+    path: String,
+    file: String,
+    staged: bool,
+    ignore_whitespace: Option<bool>,
+) -> Result<Vec<DiffHunk>, String> {
+    let ignore_whitespace = ignore_whitespace.unwrap_or(false);
+    let stdout = run_file_diff(&path, &file, staged, ignore_whitespace, None, false)?;
 
-            commits.push(CommitSummary {
-                hash: parts[0].to_string(),
-                short_hash: parts[1].to_string(),
-                subject: parts[2].to_string(),
-                author: parts[3].to_string(),
-                timestamp,
-                relative_time: parse_relative_time(seconds_ago),
-                files_changed: 0,
-                additions: 0,
-                deletions: 0,
-            });
+    // Untracked files are shown as a single all-added hunk
+    if stdout.is_empty() {
+        let file_path = PathBuf::from(&path).join(&file);
+        if file_path.exists() && !is_tracked(&path, &file) {
+            let lines = read_new_file_lines(&path, &file).unwrap_or_default();
+            let new_count = lines.len() as u32;
+            return Ok(vec![DiffHunk {
+                header: format!("@@ -0,0 +1,{} @@", new_count),
+                old_start: 0,
+                old_count: 0,
+                new_start: 1,
+                new_count,
+                lines: lines
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, content)| DiffLine {
+                        kind: "added".to_string(),
+                        content,
+                        old_line: None,
+                        new_line: Some(i as u32 + 1),
+                        whitespace_only: false,
+                    })
+                    .collect(),
+                whitespace_only: false,
+            }]);
         }
     }
 
-    // Get stats for each commit
-    for commit in &mut commits {
-        let stat_output = std::process::Command::new("git")
-            .args(["-C", &path, "show", "--stat", "--format=", &commit.hash])
-            .output();
+    Ok(parse_unified_diff(&String::from_utf8_lossy(&stdout)))
+}
 
-        if let Ok(output) = stat_output {
-            let stat_text = String::from_utf8_lossy(&output.stdout);
-            // Parse the summary line like "3 files changed, 10 insertions(+), 5 deletions(-)"
-            for line in stat_text.lines() {
-                if line.contains("changed") {
-                    let parts: Vec<&str> = line.split_whitespace().collect();
-                    for (i, part) in parts.iter().enumerate() {
-                        if *part == "file" || *part == "files" {
-                            if i > 0 {
-                                commit.files_changed = parts[i - 1].parse().unwrap_or(0);
-                            }
-                        } else if part.contains("insertion") {
-                            if i > 0 {
-                                commit.additions = parts[i - 1].parse().unwrap_or(0);
-                            }
-                        } else if part.contains("deletion") {
-                            if i > 0 {
-                                commit.deletions = parts[i - 1].parse().unwrap_or(0);
-                            }
-                        }
-                    }
-                    break;
-                }
-            }
+/// Parse a unified diff hunk header like "@@ -12,3 +12,4 @@" into
+/// (old_start, old_count, new_start, new_count)
+fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32, u32)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, _) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+
+    let parse_range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
         }
-    }
+    };
 
-    Ok(commits)
+    let (old_start, old_count) = parse_range(old)?;
+    let (new_start, new_count) = parse_range(new)?;
+    Some((old_start, old_count, new_start, new_count))
 }
 
+/// Per-line change markers relative to HEAD for the editor gutter.
+/// With `staged`, compares the index against HEAD; otherwise the working tree.
 #[tauri::command]
-pub fn get_commit_files(path: String, hash: String) -> Result<Vec<CommitFile>, String> {
+pub fn get_gutter_diff(
+    path: String,
+    file: String,
+    staged: bool,
+) -> Result<Vec<GutterMarker>, String> {
+    let mut args = vec!["-C", &path, "diff", "-U0", "--no-color"];
+    if staged {
+        args.push("--staged");
+    } else {
+        args.push("HEAD");
+    }
+    args.push("--");
+    args.push(&file);
+
     let output = std::process::Command::new("git")
-        .args([
-            "-C",
-            &path,
-            "show",
-            "--numstat",
-            "--name-status",
-            "--format=",
-            &hash,
-        ])
-        .output()
+        .args(&args)
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if !output.status.success() {
@@ -453,108 +875,2350 @@ pub fn get_commit_files(path: String, hash: String) -> Result<Vec<CommitFile>, S
     }
 
     let text = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = text.lines().collect();
+    let mut markers = Vec::new();
 
-    let mut files = Vec::new();
-    let mut numstat_map: HashMap<String, (i32, i32)> = HashMap::new();
+    for line in text.lines() {
+        let Some((_, old_count, new_start, new_count)) = parse_hunk_header(line) else {
+            continue;
+        };
 
-    // First pass: collect numstat (additions/deletions)
-    for line in &lines {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() == 3 {
-            let additions: i32 = parts[0].parse().unwrap_or(0);
-            let deletions: i32 = parts[1].parse().unwrap_or(0);
-            let file_path = parts[2].to_string();
-            numstat_map.insert(file_path, (additions, deletions));
+        if new_count == 0 {
+            // Pure deletion: new_start is the line before the removed block
+            markers.push(GutterMarker {
+                line: new_start + 1,
+                kind: "removed".to_string(),
+            });
+            continue;
+        }
+
+        let kind = if old_count == 0 { "added" } else { "modified" };
+        for line in new_start..new_start + new_count {
+            markers.push(GutterMarker {
+                line,
+                kind: kind.to_string(),
+            });
         }
     }
 
-    // Second pass: collect name-status
-    for line in &lines {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 2 && parts[0].len() == 1 {
-            let status_code = parts[0];
-            let file_path = if parts.len() == 3 {
-                // Rename: "R\told_path\tnew_path"
-                parts[2].to_string()
-            } else {
-                parts[1].to_string()
-            };
+    Ok(markers)
+}
 
-            let (additions, deletions) = numstat_map.get(&file_path).copied().unwrap_or((0, 0));
+#[tauri::command]
+pub fn stage_files(
+    path: String,
+    files: Vec<String>,
+    force: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let mut args = vec!["-C".to_string(), path, "add".to_string(), "-v".to_string()];
+    // Without force, git silently skips ignored files
+    if force.unwrap_or(false) {
+        args.push("-f".to_string());
+    }
+    args.push("--".to_string());
+    args.extend(files);
 
-            let status = match status_code {
-                "A" => "added",
-                "M" => "modified",
-                "D" => "deleted",
-                "R" => "renamed",
-                _ => "modified",
-            };
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
 
-            files.push(CommitFile {
-                path: file_path,
-                status: status.to_string(),
-                additions,
-                deletions,
-            });
-        }
+    if output.status.success() {
+        // `git add -v` reports each staged path as: add '<path>'
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| l.strip_prefix("add '")?.strip_suffix('\''))
+            .map(|l| l.to_string())
+            .collect())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
     }
-
-    Ok(files)
 }
 
+/// Whether a path is excluded by gitignore rules, and which rule matched.
+/// Lets the staging UI explain why `git add` silently skipped a file.
 #[tauri::command]
-pub fn get_commit_diff(path: String, hash: String, file: Option<String>) -> Result<String, String> {
-    let mut args = vec!["-C", &path, "show", &hash];
+pub fn check_ignored(path: String, file: String) -> Result<IgnoreCheck, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "check-ignore", "-v", "--", &file])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
 
-    let file_ref;
-    if let Some(ref f) = file {
-        args.push("--");
-        file_ref = f.as_str();
-        args.push(file_ref);
+    // Exit code 1 means no rule matched
+    if output.status.code() == Some(1) {
+        return Ok(IgnoreCheck {
+            ignored: false,
+            source: None,
+            line: None,
+            pattern: None,
+        });
+    }
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_ignore_rule(&stdout))
+}
+
+/// Parse a `check-ignore -v` line: <source>:<line>:<pattern>\t<path>
+fn parse_ignore_rule(output: &str) -> IgnoreCheck {
+    let rule = output.split('\t').next().unwrap_or_default();
+    // Split from the left so patterns containing ':' stay intact
+    let mut parts = rule.splitn(3, ':');
+    let source = parts.next().map(|s| s.to_string());
+    let line = parts.next().and_then(|l| l.parse().ok());
+    let pattern = parts.next().map(|p| p.to_string());
+
+    IgnoreCheck {
+        // A matching negated rule ("!pattern") re-includes the path
+        ignored: !pattern.as_deref().unwrap_or_default().starts_with('!'),
+        source,
+        line,
+        pattern,
     }
+}
 
+#[tauri::command]
+pub fn stage_all(path: String) -> Result<(), String> {
     let output = std::process::Command::new("git")
-        .args(&args)
-        .output()
+        .args(["-C", &path, "add", "-A"])
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        Ok(())
     } else {
         Err(String::from_utf8_lossy(&output.stderr).to_string())
     }
 }
 
 #[tauri::command]
-pub fn clone_repo(url: String, destination: String) -> Result<String, String> {
+pub fn unstage_files(path: String, files: Vec<String>) -> Result<(), String> {
+    let mut args = vec![
+        "-C".to_string(),
+        path,
+        "reset".to_string(),
+        "HEAD".to_string(),
+        "--".to_string(),
+    ];
+    args.extend(files);
+
     let output = std::process::Command::new("git")
-        .args(["clone", &url, &destination])
-        .output()
+        .args(&args)
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if output.status.success() {
-        Ok(destination)
+        Ok(())
     } else {
         Err(String::from_utf8_lossy(&output.stderr).to_string())
     }
 }
 
 #[tauri::command]
-pub fn get_git_remote(path: String) -> Result<Option<String>, String> {
+pub fn unstage_all(path: String) -> Result<(), String> {
     let output = std::process::Command::new("git")
-        .args(["-C", &path, "remote", "get-url", "origin"])
-        .output()
+        .args(["-C", &path, "reset", "HEAD"])
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if output.status.success() {
-        let remote = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        Ok(if remote.is_empty() {
-            None
-        } else {
-            Some(remote)
-        })
+        Ok(())
     } else {
-        Ok(None)
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+#[tauri::command]
+pub fn discard_changes(path: String, file: String, is_untracked: bool) -> Result<(), String> {
+    if is_untracked {
+        // Delete untracked file
+        let file_path = PathBuf::from(&path).join(&file);
+        fs::remove_file(&file_path).map_err(|e| e.to_string())?;
+    } else {
+        // Restore tracked file
+        let output = std::process::Command::new("git")
+            .args(["-C", &path, "checkout", "--", &file])
+            .timed_output()
+            .map_err(|e| e.to_string())?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Discard all working tree changes. Returns the affected files; with
+/// `dry_run` nothing is changed, otherwise `confirm` must be set explicitly.
+#[tauri::command]
+pub fn discard_all_changes(
+    path: String,
+    include_untracked: bool,
+    dry_run: bool,
+    confirm: bool,
+) -> Result<Vec<String>, String> {
+    if !dry_run && !confirm {
+        return Err("Discarding all changes requires confirmation".to_string());
+    }
+
+    let diff_output = std::process::Command::new("git")
+        .args(["-C", &path, "diff", "--name-only"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !diff_output.status.success() {
+        return Err(String::from_utf8_lossy(&diff_output.stderr).to_string());
+    }
+
+    let mut affected: Vec<String> = String::from_utf8_lossy(&diff_output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    // `checkout -- .` fails when nothing is tracked yet, so only run it when
+    // there are tracked changes to discard
+    let has_tracked_changes = !affected.is_empty();
+
+    if include_untracked {
+        let clean_output = std::process::Command::new("git")
+            .args(["-C", &path, "clean", "-nd"])
+            .timed_output()
+            .map_err(|e| e.to_string())?;
+
+        if !clean_output.status.success() {
+            return Err(String::from_utf8_lossy(&clean_output.stderr).to_string());
+        }
+
+        affected.extend(
+            String::from_utf8_lossy(&clean_output.stdout)
+                .lines()
+                .filter_map(|line| line.strip_prefix("Would remove "))
+                .map(|file| file.to_string()),
+        );
+    }
+
+    if dry_run {
+        return Ok(affected);
+    }
+
+    if has_tracked_changes {
+        let output = std::process::Command::new("git")
+            .args(["-C", &path, "checkout", "--", "."])
+            .timed_output()
+            .map_err(|e| e.to_string())?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+    }
+
+    if include_untracked {
+        let output = std::process::Command::new("git")
+            .args(["-C", &path, "clean", "-fd"])
+            .timed_output()
+            .map_err(|e| e.to_string())?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        }
+    }
+
+    Ok(affected)
+}
+
+/// In-progress operation the repository is in the middle of, if any
+fn repo_state(path: &str) -> Result<Option<&'static str>, String> {
+    let git_dir = git_dir(path)?;
+    let state = if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        Some("rebase")
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        Some("merge")
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        Some("cherry-pick")
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        Some("revert")
+    } else if git_dir.join("BISECT_LOG").exists() {
+        Some("bisect")
+    } else {
+        None
+    };
+    Ok(state)
+}
+
+fn git_config_value(path: &str, key: &str) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", path, "config", "--get", key])
+        .timed_output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+/// Everything the commit button needs to decide whether committing makes
+/// sense right now, gathered in one call
+#[tauri::command]
+pub fn precommit_summary(path: String) -> Result<PrecommitSummary, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "status", "--porcelain=v1"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let mut summary = PrecommitSummary {
+        has_staged: false,
+        has_unstaged: false,
+        has_untracked: false,
+        has_conflicts: false,
+        identity_configured: git_config_value(&path, "user.name").is_some()
+            && git_config_value(&path, "user.email").is_some(),
+        repo_state: repo_state(&path)?.map(String::from),
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let code = line.get(..2).unwrap_or("");
+        match code {
+            "??" => summary.has_untracked = true,
+            "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU" => summary.has_conflicts = true,
+            _ => {
+                let mut chars = code.chars();
+                summary.has_staged |= chars.next().is_some_and(|c| c != ' ');
+                summary.has_unstaged |= chars.next().is_some_and(|c| c != ' ');
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn git_commit(path: String, message: String) -> Result<String, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "commit", "-m", &message])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// Amend the last commit with whatever is staged. Without a message the
+/// existing one is kept.
+#[tauri::command]
+pub fn git_commit_amend(path: String, message: Option<String>) -> Result<String, String> {
+    let head = std::process::Command::new("git")
+        .args(["-C", &path, "rev-parse", "--verify", "--quiet", "HEAD"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !head.status.success() {
+        return Err("Nothing to amend: this repository has no commits yet".to_string());
+    }
+
+    let mut args = vec!["-C", &path, "commit", "--amend"];
+    match message.as_deref().filter(|m| !m.trim().is_empty()) {
+        Some(message) => args.extend(["-m", message]),
+        None => args.push("--no-edit"),
+    }
+
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+#[tauri::command]
+pub fn git_push(path: String) -> Result<String, String> {
+    // First try normal push
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "push"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    // If that fails, try to set upstream
+    let branch_output = std::process::Command::new("git")
+        .args(["-C", &path, "branch", "--show-current"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    let branch = String::from_utf8_lossy(&branch_output.stdout)
+        .trim()
+        .to_string();
+
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "push", "-u", "origin", &branch])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// Pull from the upstream. `mode` is "default", "ff-only", or "rebase".
+/// When the pull stops on conflicts, the error lists the conflicted files.
+#[tauri::command]
+pub fn git_pull(path: String, mode: Option<String>) -> Result<String, String> {
+    let mut args = vec!["-C", &path, "pull"];
+    match mode.as_deref().unwrap_or("default") {
+        "default" => {}
+        "ff-only" => args.push("--ff-only"),
+        "rebase" => args.push("--rebase"),
+        other => return Err(format!("Unknown pull mode: {}", other)),
+    }
+
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    let conflicts =
+        git_stdout(&path, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "Pull stopped on merge conflicts in:\n{}",
+            conflicts
+        ));
+    }
+
+    Err(describe_remote_error(&String::from_utf8_lossy(
+        &output.stderr,
+    )))
+}
+
+/// Fetch from a remote (origin by default), optionally pruning deleted
+/// branches, and report which refs changed
+#[tauri::command]
+pub fn git_fetch(path: String, remote: Option<String>, prune: bool) -> Result<FetchResult, String> {
+    let remote = remote
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "origin".to_string());
+    if remote.starts_with('-') {
+        return Err(format!("Invalid remote: {}", remote));
+    }
+
+    let mut args = vec!["-C", &path, "fetch"];
+    if prune {
+        args.push("--prune");
+    }
+    args.push(&remote);
+
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(describe_remote_error(&stderr));
+    }
+
+    // Ref update lines: " <flag> <summary> <from> -> <to> [(<reason>)]"
+    let mut result = FetchResult {
+        updated: Vec::new(),
+        pruned: Vec::new(),
+    };
+    for line in stderr.lines() {
+        let Some(rest) = line.strip_prefix(' ') else {
+            continue;
+        };
+        let mut chars = rest.chars();
+        let Some(flag) = chars.next() else {
+            continue;
+        };
+        let Some(name) = chars
+            .as_str()
+            .split_once(" -> ")
+            .and_then(|(_, to)| to.split_whitespace().next())
+        else {
+            continue;
+        };
+
+        let status = match flag {
+            '-' => {
+                result.pruned.push(name.to_string());
+                continue;
+            }
+            '*' => "new",
+            ' ' => "updated",
+            '+' => "forced",
+            't' => "tag",
+            '!' => "rejected",
+            _ => continue,
+        };
+        result.updated.push(FetchedRef {
+            name: name.to_string(),
+            status: status.to_string(),
+        });
+    }
+
+    Ok(result)
+}
+
+// git blame reports an all-zero hash (40 or 64 digits) for lines that
+// haven't been committed yet
+fn is_uncommitted_hash(hash: &str) -> bool {
+    !hash.is_empty() && hash.bytes().all(|b| b == b'0')
+}
+
+/// Line-by-line blame for a file in the working tree
+#[tauri::command]
+pub fn git_blame(path: String, file: String) -> Result<Vec<BlameLine>, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "blame", "--line-porcelain", "--", &file])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut lines = Vec::new();
+    let mut current: Option<BlameLine> = None;
+
+    for line in text.lines() {
+        // Each entry ends with the line's content, prefixed by a tab
+        if line.starts_with('\t') {
+            if let Some(mut blame) = current.take() {
+                if !blame.committed {
+                    blame.author = "Not Committed Yet".to_string();
+                    blame.summary = "Not Committed Yet".to_string();
+                }
+                lines.push(blame);
+            }
+            continue;
+        }
+
+        match &mut current {
+            None => {
+                // Header: <hash> <original line> <final line> [<group size>]
+                let mut parts = line.split_whitespace();
+                let (Some(hash), Some(_), Some(final_line)) =
+                    (parts.next(), parts.next(), parts.next())
+                else {
+                    continue;
+                };
+                current = Some(BlameLine {
+                    line_number: final_line.parse().unwrap_or(0),
+                    commit_hash: hash.to_string(),
+                    author: String::new(),
+                    timestamp: 0,
+                    summary: String::new(),
+                    committed: !is_uncommitted_hash(hash),
+                });
+            }
+            Some(blame) => {
+                if let Some(author) = line.strip_prefix("author ") {
+                    blame.author = author.to_string();
+                } else if let Some(time) = line.strip_prefix("author-time ") {
+                    blame.timestamp = time.parse().unwrap_or(0);
+                } else if let Some(summary) = line.strip_prefix("summary ") {
+                    blame.summary = summary.to_string();
+                }
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+#[tauri::command]
+pub fn get_commit_history(path: String, limit: i32) -> Result<Vec<CommitSummary>, String> {
+    // Get commit info with custom format
+    let format = "%H|%h|%s|%an|%ct";
+    let output = std::process::Command::new("git")
+        .args([
+            "-C",
+            &path,
+            "log",
+            &format!("--format={}", format),
+            &format!("-n{}", limit),
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    let mut commits = Vec::new();
+    let log_text = String::from_utf8_lossy(&output.stdout);
+
+    for line in log_text.lines() {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() >= 5 {
+            let timestamp: i64 = parts[4].parse().unwrap_or(0);
+            let seconds_ago = now - timestamp;
+
+            commits.push(CommitSummary {
+                hash: parts[0].to_string(),
+                short_hash: parts[1].to_string(),
+                subject: parts[2].to_string(),
+                author: parts[3].to_string(),
+                timestamp,
+                relative_time: parse_relative_time(seconds_ago),
+                files_changed: 0,
+                additions: 0,
+                deletions: 0,
+            });
+        }
+    }
+
+    // Get stats for each commit
+    for commit in &mut commits {
+        let stat_output = std::process::Command::new("git")
+            .args(["-C", &path, "show", "--stat", "--format=", &commit.hash])
+            .timed_output();
+
+        if let Ok(output) = stat_output {
+            let stat_text = String::from_utf8_lossy(&output.stdout);
+            // Parse the summary line like "3 files changed, 10 insertions(+), 5 deletions(-)"
+            for line in stat_text.lines() {
+                if line.contains("changed") {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    for (i, part) in parts.iter().enumerate() {
+                        if *part == "file" || *part == "files" {
+                            if i > 0 {
+                                commit.files_changed = parts[i - 1].parse().unwrap_or(0);
+                            }
+                        } else if part.contains("insertion") {
+                            if i > 0 {
+                                commit.additions = parts[i - 1].parse().unwrap_or(0);
+                            }
+                        } else if part.contains("deletion") {
+                            if i > 0 {
+                                commit.deletions = parts[i - 1].parse().unwrap_or(0);
+                            }
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(commits)
+}
+
+#[tauri::command]
+pub fn get_commit_files(path: String, hash: String) -> Result<Vec<CommitFile>, String> {
+    let output = std::process::Command::new("git")
+        .args([
+            "-C",
+            &path,
+            "show",
+            "--numstat",
+            "--name-status",
+            "--format=",
+            &hash,
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = text.lines().collect();
+
+    let mut files = Vec::new();
+    let mut numstat_map: HashMap<String, (i32, i32)> = HashMap::new();
+
+    // First pass: collect numstat (additions/deletions)
+    for line in &lines {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() == 3 {
+            let additions: i32 = parts[0].parse().unwrap_or(0);
+            let deletions: i32 = parts[1].parse().unwrap_or(0);
+            let file_path = parts[2].to_string();
+            numstat_map.insert(file_path, (additions, deletions));
+        }
+    }
+
+    // Second pass: collect name-status
+    for line in &lines {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() >= 2 && parts[0].len() == 1 {
+            let status_code = parts[0];
+            let file_path = if parts.len() == 3 {
+                // Rename: "R\told_path\tnew_path"
+                parts[2].to_string()
+            } else {
+                parts[1].to_string()
+            };
+
+            let (additions, deletions) = numstat_map.get(&file_path).copied().unwrap_or((0, 0));
+
+            let status = match status_code {
+                "A" => "added",
+                "M" => "modified",
+                "D" => "deleted",
+                "R" => "renamed",
+                _ => "modified",
+            };
+
+            files.push(CommitFile {
+                path: file_path,
+                status: status.to_string(),
+                additions,
+                deletions,
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+/// Run `git log --no-walk` over `hashes` with one diff format, returning each
+/// commit's output lines keyed by the hash as given
+fn log_per_commit(
+    path: &str,
+    hashes: &[String],
+    format_flag: &str,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    // \x01 can't appear in git's own output lines, so it marks commit starts
+    let mut args = vec![
+        "-C",
+        path,
+        "log",
+        "--no-walk=unsorted",
+        "--format=%x01%H",
+        format_flag,
+    ];
+    args.extend(hashes.iter().map(String::as_str));
+
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let mut by_commit: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(full_hash) = line.strip_prefix('\x01') {
+            current = Some(full_hash.to_string());
+            by_commit.entry(full_hash.to_string()).or_default();
+        } else if let (Some(hash), false) = (&current, line.is_empty()) {
+            by_commit
+                .entry(hash.clone())
+                .or_default()
+                .push(line.to_string());
+        }
+    }
+    Ok(by_commit)
+}
+
+/// The new path of a numstat entry, which writes renames as `old => new` or
+/// `dir/{old => new}/file`
+fn numstat_path(path: &str) -> String {
+    if let (Some(open), Some(close)) = (path.find('{'), path.find('}')) {
+        if let Some((_, new)) = path[open + 1..close].split_once(" => ") {
+            // Either side of the braces may be empty, e.g. `{ => sub}/file`
+            return format!("{}{}{}", &path[..open], new, &path[close + 1..]).replace("//", "/");
+        }
+    }
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// File lists for several commits at once, keyed by the hashes as passed.
+/// Uses one `rev-parse` and two `git log --no-walk` runs in total instead of
+/// one `git show` per commit.
+#[tauri::command]
+pub fn get_commits_files(
+    path: String,
+    hashes: Vec<String>,
+) -> Result<HashMap<String, Vec<CommitFile>>, String> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+    if let Some(bad) = hashes
+        .iter()
+        .find(|h| h.starts_with('-') || h.trim().is_empty())
+    {
+        return Err(format!("Invalid commit hash: {}", bad));
+    }
+
+    // Callers may pass short hashes; git log reports full ones
+    let revs: Vec<String> = hashes.iter().map(|h| format!("{}^{{commit}}", h)).collect();
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "rev-parse"])
+        .args(&revs)
+        .arg("--")
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        // "fatal: bad revision '<hash>^{commit}'"
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.split('\'').nth(1) {
+            Some(rev) => format!("Unknown commit: {}", rev.trim_end_matches("^{commit}")),
+            None => stderr.trim().to_string(),
+        });
+    }
+    // rev-parse echoes the trailing "--"
+    let full_hashes: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| *line != "--")
+        .map(String::from)
+        .collect();
+    if full_hashes.len() != hashes.len() {
+        return Err("Failed to resolve commits".to_string());
+    }
+
+    let numstats = log_per_commit(&path, &full_hashes, "--numstat")?;
+    let name_statuses = log_per_commit(&path, &full_hashes, "--name-status")?;
+
+    let mut result = HashMap::new();
+    for (hash, full_hash) in hashes.into_iter().zip(full_hashes) {
+        let numstat_map: HashMap<String, (i32, i32)> = numstats
+            .get(&full_hash)
+            .into_iter()
+            .flatten()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                let additions = parts.next()?.parse().unwrap_or(0);
+                let deletions = parts.next()?.parse().unwrap_or(0);
+                Some((numstat_path(parts.next()?), (additions, deletions)))
+            })
+            .collect();
+
+        let files = name_statuses
+            .get(&full_hash)
+            .into_iter()
+            .flatten()
+            .filter_map(|line| {
+                let parts: Vec<&str> = line.split('\t').collect();
+                // Renames and copies list "old\tnew"; report the new path
+                let file_path = *parts.last()?;
+                if parts.len() < 2 {
+                    return None;
+                }
+                let status = match parts[0].chars().next() {
+                    Some('A') => "added",
+                    Some('D') => "deleted",
+                    Some('R') => "renamed",
+                    _ => "modified",
+                };
+                let (additions, deletions) = numstat_map.get(file_path).copied().unwrap_or((0, 0));
+                Some(CommitFile {
+                    path: file_path.to_string(),
+                    status: status.to_string(),
+                    additions,
+                    deletions,
+                })
+            })
+            .collect();
+
+        result.insert(hash, files);
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_commit_diff(path: String, hash: String, file: Option<String>) -> Result<String, String> {
+    let mut args = vec!["-C", &path, "show", &hash];
+
+    let file_ref;
+    if let Some(ref f) = file {
+        args.push("--");
+        file_ref = f.as_str();
+        args.push(file_ref);
+    }
+
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// Why a cherry-pick or revert didn't apply. `conflicts` lists unmerged
+/// files and `in_progress` names the operation left for the user to finish.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyCommitError {
+    message: String,
+    conflicts: Vec<String>,
+    in_progress: Option<String>,
+}
+
+impl From<String> for ApplyCommitError {
+    fn from(message: String) -> Self {
+        ApplyCommitError {
+            message,
+            conflicts: Vec::new(),
+            in_progress: None,
+        }
+    }
+}
+
+fn unmerged_files(path: &str) -> Vec<String> {
+    git_stdout(path, &["diff", "--name-only", "--diff-filter=U"])
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// Run a cherry-pick or revert of `hash`, mapping conflicts and common
+/// failures to structured errors. `action` is "cherry-pick" or "revert".
+fn apply_commit(
+    path: &str,
+    action: &str,
+    hash: &str,
+    extra: &[&str],
+) -> Result<(), ApplyCommitError> {
+    if hash.starts_with('-') {
+        return Err(format!("Invalid commit: {}", hash).into());
+    }
+    if let Some(state) = repo_state(path)? {
+        return Err(ApplyCommitError {
+            message: format!(
+                "A {} is already in progress; finish or abort it first",
+                state
+            ),
+            conflicts: unmerged_files(path),
+            in_progress: Some(state.to_string()),
+        });
+    }
+    let output = std::process::Command::new("git")
+        .args(["-C", path, action])
+        .args(extra)
+        .arg(hash)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let conflicts = unmerged_files(path);
+    if !conflicts.is_empty() {
+        let verb = if action == "revert" {
+            "Revert"
+        } else {
+            "Cherry-pick"
+        };
+        return Err(ApplyCommitError {
+            message: format!("{} of {} stopped on conflicts", verb, hash),
+            conflicts,
+            in_progress: Some(action.to_string()),
+        });
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = if stderr.contains("would be overwritten") {
+        "Local changes would be overwritten; commit or stash them first".to_string()
+    } else if stderr.contains("bad revision") || stderr.contains("bad object") {
+        format!("Commit {} not found", hash)
+    } else if stderr.contains("is a merge but no -m option") {
+        format!("Commit {} is a merge commit", hash)
+    } else if stderr.contains("now empty") {
+        // Leave the index as it was rather than an empty pick pending
+        let _ = std::process::Command::new("git")
+            .args(["-C", path, action, "--abort"])
+            .timed_output();
+        format!("Commit {} is already applied", hash)
+    } else {
+        stderr.trim().to_string()
+    };
+    Err(message.into())
+}
+
+/// Cherry-pick a commit onto HEAD. When it stops on conflicts, the error
+/// lists the conflicted files; the repo is left mid-cherry-pick to resolve.
+#[tauri::command]
+pub fn cherry_pick_commit(path: String, hash: String) -> Result<(), ApplyCommitError> {
+    apply_commit(&path, "cherry-pick", &hash, &[])
+}
+
+/// Revert a commit, either as a new commit or (with `no_commit`) as staged
+/// changes only. Conflicts are reported like `cherry_pick_commit`.
+#[tauri::command]
+pub fn revert_commit(path: String, hash: String, no_commit: bool) -> Result<(), ApplyCommitError> {
+    let extra: &[&str] = if no_commit {
+        &["--no-commit"]
+    } else {
+        &["--no-edit"]
+    };
+    apply_commit(&path, "revert", &hash, extra)
+}
+
+/// Move HEAD (and the current branch) to `target`, HEAD when empty. `mode`
+/// must be exactly "soft", "mixed", or "hard"; hard discards local changes.
+#[tauri::command]
+pub fn git_reset(path: String, target: String, mode: String) -> Result<(), String> {
+    let flag = match mode.as_str() {
+        "soft" => "--soft",
+        "mixed" => "--mixed",
+        "hard" => "--hard",
+        other => return Err(format!("Unknown reset mode: {}", other)),
+    };
+    let target = match target.trim() {
+        "" => "HEAD",
+        target if target.starts_with('-') => return Err(format!("Invalid target: {}", target)),
+        target => target,
+    };
+    let commit = git_stdout(
+        &path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", target),
+        ],
+    )
+    .ok()
+    .filter(|sha| !sha.is_empty())
+    .ok_or_else(|| format!("Commit {} not found", target))?;
+
+    git_stdout(&path, &["reset", "--quiet", flag, &commit, "--"]).map(|_| ())
+}
+
+/// Overwrite a working tree file with its contents at `ref` (the index is
+/// left alone)
+#[tauri::command]
+pub fn restore_file_to(path: String, file: String, r#ref: String) -> Result<(), String> {
+    let source = r#ref.trim();
+    if source.is_empty() || source.starts_with('-') {
+        return Err(format!("Invalid ref: {}", r#ref));
+    }
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "restore", "--source", source, "--", &file])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("did not match any file") {
+        Err(format!("{} does not exist at {}", file, source))
+    } else if stderr.contains("could not resolve") || stderr.contains("invalid reference") {
+        Err(format!("Unknown ref: {}", source))
+    } else {
+        Err(stderr.trim().to_string())
+    }
+}
+
+/// Percentage from a progress line like "Receiving objects:  45% (450/1000)"
+fn progress_percent(line: &str) -> Option<u32> {
+    let (before, _) = line.split_once('%')?;
+    before
+        .rsplit(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn run_clone(
+    url: &str,
+    destination: &str,
+    depth: Option<u32>,
+    branch: Option<&str>,
+    operation_id: String,
+    clones: &CloneMap,
+    app: &AppHandle,
+) -> Result<String, String> {
+    let depth = depth.map(|d| d.to_string());
+    let mut args = vec!["clone", "--progress"];
+    if let Some(depth) = depth.as_deref() {
+        args.extend(["--depth", depth]);
+    }
+    if let Some(branch) = branch {
+        args.extend(["--branch", branch]);
+    }
+    args.extend(["--", url, destination]);
+
+    let created_destination = !Path::new(destination).exists();
+    let mut command = std::process::Command::new("git");
+    command
+        .args(&args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    // Own process group, so cancelling also stops the transport helpers
+    // (git-remote-https, index-pack) git spawns
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+
+    let stderr = child.stderr.take();
+    clones.lock().unwrap().insert(
+        operation_id.clone(),
+        CloneOperation {
+            child,
+            destination: destination.to_string(),
+            created_destination,
+        },
+    );
+
+    // Reaches EOF once git and its helpers, which share the pipe, exit or
+    // are killed by cancel_clone
+    let stderr_text = match stderr {
+        Some(stderr) => read_progress(stderr, |line| {
+            let _ = app.emit(
+                "clone-progress",
+                CloneProgress {
+                    destination: destination.to_string(),
+                    message: line.to_string(),
+                    percent: progress_percent(line),
+                },
+            );
+        }),
+        None => String::new(),
+    };
+
+    let operation = clones.lock().unwrap().remove(&operation_id);
+    let Some(mut operation) = operation else {
+        return Err("Clone cancelled".to_string());
+    };
+    let status = operation.child.wait().map_err(|e| e.to_string())?;
+
+    if status.success() {
+        Ok(destination.to_string())
+    } else {
+        Err(stderr_text)
+    }
+}
+
+/// Clone a repository, optionally shallow (`depth`) and of a single
+/// `branch`. Progress is emitted as `clone-progress` events. Pass an
+/// `operation_id` to be able to abort it with `cancel_clone` (defaults to
+/// the destination path).
+#[tauri::command]
+pub async fn clone_repo(
+    url: String,
+    destination: String,
+    operation_id: Option<String>,
+    depth: Option<u32>,
+    branch: Option<String>,
+    app: AppHandle,
+    clones: tauri::State<'_, CloneMap>,
+) -> Result<String, String> {
+    let operation_id = operation_id.unwrap_or_else(|| destination.clone());
+    let clones = clones.inner().clone();
+    let branch = branch.filter(|b| !b.trim().is_empty());
+    tauri::async_runtime::spawn_blocking(move || {
+        run_clone(
+            &url,
+            &destination,
+            depth.filter(|d| *d > 0),
+            branch.as_deref(),
+            operation_id,
+            &clones,
+            &app,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Abort a running clone and delete what it cloned. A destination directory
+/// that existed beforehand is emptied but kept. Returns whether anything was
+/// removed.
+#[tauri::command]
+pub fn cancel_clone(
+    operation_id: String,
+    clones: tauri::State<'_, CloneMap>,
+) -> Result<bool, String> {
+    let operation = clones.lock().unwrap().remove(&operation_id);
+    let mut operation =
+        operation.ok_or_else(|| format!("No clone running for {}", operation_id))?;
+
+    // git leads its own process group (see run_clone)
+    #[cfg(unix)]
+    unsafe {
+        libc::killpg(operation.child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    let _ = operation.child.kill();
+    let _ = operation.child.wait();
+
+    let destination = PathBuf::from(&operation.destination);
+    if !destination.exists() {
+        return Ok(false);
+    }
+    if operation.created_destination {
+        fs::remove_dir_all(&destination).map_err(|e| e.to_string())?;
+        return Ok(true);
+    }
+    // git only clones into an empty directory, so everything in it is ours
+    let mut removed = false;
+    for entry in fs::read_dir(&destination).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.is_dir() && !path.is_symlink() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        }
+        .map_err(|e| e.to_string())?;
+        removed = true;
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub fn get_git_remote(path: String) -> Result<Option<String>, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "remote", "get-url", "origin"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        let remote = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(if remote.is_empty() {
+            None
+        } else {
+            Some(remote)
+        })
+    } else {
+        Ok(None)
+    }
+}
+
+/// All remotes with their fetch and push URLs, in `git remote -v` order
+#[tauri::command]
+pub fn list_git_remotes(path: String) -> Result<Vec<RemoteInfo>, String> {
+    let output = git_stdout(&path, &["remote", "-v"])?;
+
+    // Lines look like "<name>\t<url> (fetch)" and "<name>\t<url> (push)";
+    // partial clones add a filter suffix, e.g. " [blob:none]"
+    let mut remotes: Vec<RemoteInfo> = Vec::new();
+    for line in output.lines() {
+        let Some((name, rest)) = line.split_once('\t') else {
+            continue;
+        };
+        let rest = match rest.rsplit_once(" [") {
+            Some((rest, suffix)) if suffix.ends_with(']') => rest,
+            _ => rest,
+        };
+        let Some((url, kind)) = rest.rsplit_once(' ') else {
+            continue;
+        };
+
+        let index = match remotes.iter().position(|r| r.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(RemoteInfo {
+                    name: name.to_string(),
+                    fetch_url: url.to_string(),
+                    push_url: url.to_string(),
+                });
+                remotes.len() - 1
+            }
+        };
+        match kind {
+            "(fetch)" => remotes[index].fetch_url = url.to_string(),
+            "(push)" => remotes[index].push_url = url.to_string(),
+            _ => {}
+        }
+    }
+    Ok(remotes)
+}
+
+/// Run a `git remote` subcommand, turning git's common failures into
+/// readable errors
+fn run_git_remote(path: &str, name: &str, args: &[&str]) -> Result<(), String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", path, "remote"])
+        .args(args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("already exists") {
+        Err(format!("Remote {} already exists", name))
+    } else if stderr.contains("No such remote") {
+        Err(format!("Remote {} not found", name))
+    } else if stderr.contains("not a valid remote name") {
+        Err(format!("Invalid remote name: {}", name))
+    } else {
+        Err(stderr.trim().to_string())
+    }
+}
+
+/// Reject names and URLs git would parse as options
+fn check_remote_args(name: &str, url: &str) -> Result<(), String> {
+    if name.starts_with('-') {
+        return Err(format!("Invalid remote name: {}", name));
+    }
+    if url.starts_with('-') {
+        return Err(format!("Invalid remote URL: {}", url));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn add_git_remote(path: String, name: String, url: String) -> Result<(), String> {
+    let (name, url) = (name.trim(), url.trim());
+    if name.is_empty() || url.is_empty() {
+        return Err("Remote name and URL are required".to_string());
+    }
+    check_remote_args(name, url)?;
+    run_git_remote(&path, name, &["add", name, url])
+}
+
+/// Change a remote's URL (fetch and push, unless a separate push URL is set)
+#[tauri::command]
+pub fn set_remote_url(path: String, name: String, url: String) -> Result<(), String> {
+    let (name, url) = (name.trim(), url.trim());
+    if url.is_empty() {
+        return Err("Remote URL is required".to_string());
+    }
+    check_remote_args(name, url)?;
+    run_git_remote(&path, name, &["set-url", name, url])
+}
+
+/// Resolve the repository's shared .git directory (works from inside worktrees)
+fn git_common_dir(path: &str) -> Result<PathBuf, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", path, "rev-parse", "--git-common-dir"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(if dir.is_relative() {
+        PathBuf::from(path).join(dir)
+    } else {
+        dir
+    })
+}
+
+/// Resolve the git directory of the current worktree (where MERGE_HEAD and
+/// friends live), as opposed to the shared common dir
+fn git_dir(path: &str) -> Result<PathBuf, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", path, "rev-parse", "--absolute-git-dir"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Read git's stderr to the end, passing each progress line (separated by
+/// \r or \n) to `on_line`. Returns the full text for error reporting.
+fn read_progress(mut stderr: impl Read, mut on_line: impl FnMut(&str)) -> String {
+    let mut stderr_text = String::new();
+    let mut buf = [0u8; 4096];
+    let mut pending = String::new();
+    while let Ok(n) = stderr.read(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        pending.push_str(&String::from_utf8_lossy(&buf[..n]));
+        while let Some(idx) = pending.find(['\r', '\n']) {
+            let line = pending[..idx].trim().to_string();
+            pending.drain(..=idx);
+            if !line.is_empty() {
+                stderr_text.push_str(&line);
+                stderr_text.push('\n');
+                on_line(&line);
+            }
+        }
+    }
+    stderr_text.push_str(&pending);
+    stderr_text
+}
+
+fn run_git_gc(path: &str, aggressive: bool, app: &AppHandle) -> Result<GcResult, String> {
+    let git_dir = git_common_dir(path)?;
+    let size_before = dir_size(&git_dir);
+
+    let mut args = vec!["-C", path, "gc"];
+    if aggressive {
+        args.push("--aggressive");
+    }
+
+    let mut child = std::process::Command::new("git")
+        .args(&args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    let stderr_text = match child.stderr.take() {
+        Some(stderr) => read_progress(stderr, |line| {
+            let _ = app.emit(
+                "git-gc-progress",
+                GcProgress {
+                    path: path.to_string(),
+                    message: line.to_string(),
+                },
+            );
+        }),
+        None => String::new(),
+    };
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(stderr_text);
+    }
+
+    let size_after = dir_size(&git_dir);
+    Ok(GcResult {
+        size_before,
+        size_after,
+        bytes_reclaimed: size_before.saturating_sub(size_after),
+    })
+}
+
+#[tauri::command]
+pub async fn git_gc(path: String, aggressive: bool, app: AppHandle) -> Result<GcResult, String> {
+    tauri::async_runtime::spawn_blocking(move || run_git_gc(&path, aggressive, &app))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Turn common fetch/push failures into readable messages, falling back to
+/// git's own stderr
+fn describe_remote_error(stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    if lower.contains("could not resolve host") || lower.contains("network is unreachable") {
+        "Network error: could not reach the remote host".to_string()
+    } else if lower.contains("authentication failed")
+        || lower.contains("permission denied")
+        || lower.contains("could not read username")
+    {
+        "Authentication failed: check your credentials or SSH key".to_string()
+    } else if lower.contains("couldn't find remote ref") {
+        "Branch not found on the remote".to_string()
+    } else if lower.contains("does not appear to be a git repository") {
+        "Remote not found".to_string()
+    } else {
+        stderr.trim().to_string()
+    }
+}
+
+/// Fetch a remote branch into its remote-tracking ref without touching the
+/// current checkout. Returns true when the tracking ref didn't exist before.
+#[tauri::command]
+pub fn track_remote_branch(path: String, remote: String, branch: String) -> Result<bool, String> {
+    let tracking_ref = format!("refs/remotes/{}/{}", remote, branch);
+
+    let existed = std::process::Command::new("git")
+        .args([
+            "-C",
+            &path,
+            "show-ref",
+            "--verify",
+            "--quiet",
+            &tracking_ref,
+        ])
+        .timed_status()
+        .map_err(|e| e.to_string())?
+        .success();
+
+    let output = std::process::Command::new("git")
+        .args([
+            "-C",
+            &path,
+            "fetch",
+            &remote,
+            &format!("refs/heads/{}:{}", branch, tracking_ref),
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(describe_remote_error(&String::from_utf8_lossy(
+            &output.stderr,
+        )));
+    }
+
+    Ok(!existed)
+}
+
+/// For aterm run from a source checkout: fetch and report how far the
+/// checkout is behind origin/main
+#[tauri::command]
+pub fn check_self_update(repo_path: String) -> Result<SelfUpdateStatus, String> {
+    let is_git_checkout = std::process::Command::new("git")
+        .args(["-C", &repo_path, "rev-parse", "--is-inside-work-tree"])
+        .timed_output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    if !is_git_checkout {
+        return Ok(SelfUpdateStatus {
+            is_git_checkout: false,
+            behind: 0,
+            latest_subject: None,
+        });
+    }
+
+    let fetch_output = std::process::Command::new("git")
+        .args(["-C", &repo_path, "fetch", "origin", "main"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !fetch_output.status.success() {
+        return Err(describe_remote_error(&String::from_utf8_lossy(
+            &fetch_output.stderr,
+        )));
+    }
+
+    let count_output = std::process::Command::new("git")
+        .args(["-C", &repo_path, "rev-list", "--count", "HEAD..origin/main"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !count_output.status.success() {
+        return Err(String::from_utf8_lossy(&count_output.stderr).to_string());
+    }
+
+    let behind: i32 = String::from_utf8_lossy(&count_output.stdout)
+        .trim()
+        .parse()
+        .unwrap_or(0);
+
+    let latest_subject = if behind > 0 {
+        std::process::Command::new("git")
+            .args(["-C", &repo_path, "log", "-1", "--format=%s", "origin/main"])
+            .timed_output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    } else {
+        None
+    };
+
+    Ok(SelfUpdateStatus {
+        is_git_checkout: true,
+        behind,
+        latest_subject,
+    })
+}
+
+fn git_stdout(path: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", path])
+        .args(args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Resolve any ref (branch, tag, remote ref, SHA, HEAD~2, ...) to the commit
+/// it points at, along with what kind of ref it is
+#[tauri::command]
+pub fn resolve_ref(path: String, r#ref: String) -> Result<ResolvedRef, String> {
+    let name = r#ref.trim();
+    if name.is_empty() {
+        return Err("Ref is empty".to_string());
+    }
+
+    let output = std::process::Command::new("git")
+        .args(["-C", &path, "rev-parse", "--symbolic-full-name", name])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("is ambiguous") {
+        return Err(format!("Ref '{}' is ambiguous", name));
+    }
+    let full_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let full_sha = git_stdout(
+        &path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", name),
+        ],
+    )
+    .ok()
+    .filter(|sha| !sha.is_empty())
+    .ok_or_else(|| format!("Ref '{}' does not resolve to a commit", name))?;
+    let short_sha = git_stdout(&path, &["rev-parse", "--short", &full_sha])?;
+
+    let kind = if full_name.starts_with("refs/heads/") {
+        "branch"
+    } else if full_name.starts_with("refs/tags/") {
+        "tag"
+    } else if full_name.starts_with("refs/remotes/") {
+        "remote"
+    } else {
+        "commit"
+    };
+
+    // Annotated tags are tag objects rather than direct commit pointers
+    let annotated =
+        kind == "tag" && git_stdout(&path, &["cat-file", "-t", &full_name]).as_deref() == Ok("tag");
+
+    Ok(ResolvedRef {
+        full_sha,
+        short_sha,
+        kind: kind.to_string(),
+        full_name: if full_name.starts_with("refs/") {
+            Some(full_name)
+        } else {
+            None
+        },
+        annotated,
+    })
+}
+
+/// Whether the working tree is pristine and exactly at `ref`, with which of
+/// the two conditions failed so the UI can explain it
+#[tauri::command]
+pub fn is_at_ref(path: String, r#ref: String) -> Result<RefCheck, String> {
+    let name = r#ref.trim();
+    if name.is_empty() {
+        return Err("Ref is empty".to_string());
+    }
+
+    let ref_sha = git_stdout(
+        &path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", name),
+        ],
+    )
+    .ok()
+    .filter(|sha| !sha.is_empty())
+    .ok_or_else(|| format!("Ref '{}' does not resolve to a commit", name))?;
+    let head_sha = git_stdout(&path, &["rev-parse", "--verify", "--quiet", "HEAD"])
+        .ok()
+        .filter(|sha| !sha.is_empty());
+
+    let status = git_stdout(&path, &["status", "--porcelain=v1"])?;
+    let changed_files = status.lines().filter(|line| !line.is_empty()).count();
+
+    let head_matches = head_sha.as_deref() == Some(ref_sha.as_str());
+    let clean = changed_files == 0;
+    Ok(RefCheck {
+        at_ref: head_matches && clean,
+        head_matches,
+        clean,
+        head_sha,
+        ref_sha,
+        changed_files,
+    })
+}
+
+/// Parse a remote URL (SSH, scp-style, or HTTP(S), with or without
+/// credentials and `.git`) into its hosting provider and repository
+fn parse_remote_url(remote: &str) -> Option<RemoteProvider> {
+    let remote = remote.trim();
+    let (scheme, rest) = if let Some(rest) = remote.strip_prefix("http://") {
+        ("http", rest)
+    } else if let Some(rest) = remote
+        .strip_prefix("https://")
+        .or_else(|| remote.strip_prefix("ssh://"))
+        .or_else(|| remote.strip_prefix("git://"))
+    {
+        ("https", rest)
+    } else {
+        // scp-style: git@github.com:org/repo.git
+        let (host, repo) = remote.split_once(':')?;
+        return parse_remote_url(&format!("https://{}/{}", host, repo));
+    };
+
+    let rest = rest.split_once('@').map(|(_, r)| r).unwrap_or(rest);
+    let (host, path) = rest.split_once('/')?;
+    // Drop any port: SSH ports don't apply to the web host
+    let host = host.split(':').next().unwrap_or(host);
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    // GitLab subgroups make the owner everything before the last segment
+    let (owner, repo) = path.rsplit_once('/')?;
+    if host.is_empty() || owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+
+    let provider = match host {
+        "github.com" => "github",
+        "gitlab.com" => "gitlab",
+        "bitbucket.org" => "bitbucket",
+        _ => "self-hosted",
+    };
+
+    Some(RemoteProvider {
+        provider: provider.to_string(),
+        host: host.to_string(),
+        owner: owner.to_string(),
+        repo: repo.to_string(),
+        web_url: format!("{}://{}/{}", scheme, host, path),
+    })
+}
+
+/// Identify where the `origin` remote is hosted. Returns None when there is
+/// no remote (or its URL can't be parsed).
+#[tauri::command]
+pub fn detect_remote_provider(path: String) -> Result<Option<RemoteProvider>, String> {
+    Ok(get_git_remote(path)?.and_then(|remote| parse_remote_url(&remote)))
+}
+
+/// Build a web permalink to a commit on the `origin` remote, optionally
+/// deep-linking to a file (and line) at that commit
+#[tauri::command]
+pub fn get_commit_permalink(
+    path: String,
+    hash: String,
+    file: Option<String>,
+    line: Option<u32>,
+) -> Result<String, String> {
+    let remote = detect_remote_provider(path.clone())?.ok_or("No recognized origin remote")?;
+    let base = &remote.web_url;
+    let sha = git_stdout(
+        &path,
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", hash)],
+    )?;
+
+    // Self-hosted GitLab and Bitbucket keep their URL layout, and usually
+    // their name in the host
+    let host = remote.host.to_lowercase();
+    let url = if host.contains("bitbucket") {
+        match file {
+            Some(file) => {
+                let anchor = line.map(|l| format!("#lines-{}", l)).unwrap_or_default();
+                format!("{}/src/{}/{}{}", base, sha, file, anchor)
+            }
+            None => format!("{}/commits/{}", base, sha),
+        }
+    } else {
+        // GitHub, GitLab, and most self-hosted forges share this layout;
+        // GitLab prefixes it with /-/
+        let prefix = if host.contains("gitlab") { "/-" } else { "" };
+        match file {
+            Some(file) => {
+                let anchor = line.map(|l| format!("#L{}", l)).unwrap_or_default();
+                format!("{}{}/blob/{}/{}{}", base, prefix, sha, file, anchor)
+            }
+            None => format!("{}{}/commit/{}", base, prefix, sha),
+        }
+    };
+
+    Ok(url)
+}
+
+const CHECKPOINT_REFS: &str = "refs/aterm/checkpoints";
+
+/// Snapshot the current HEAD plus uncommitted tracked changes to a dedicated
+/// ref without touching the working tree. The checkpoint commit's first
+/// parent is HEAD; its second parent (if any) is the `git stash create`
+/// commit holding the changes.
+#[tauri::command]
+pub fn create_checkpoint(path: String, label: Option<String>) -> Result<Checkpoint, String> {
+    let head = git_stdout(&path, &["rev-parse", "--verify", "HEAD"])?;
+    let stash = git_stdout(&path, &["stash", "create"])?;
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| "Checkpoint".to_string());
+
+    let tree = format!("{}^{{tree}}", if stash.is_empty() { &head } else { &stash });
+    let mut args = vec!["commit-tree", &tree, "-p", &head];
+    if !stash.is_empty() {
+        args.extend(["-p", &stash]);
+    }
+    args.extend(["-m", &label]);
+    let sha = git_stdout(&path, &args)?;
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let ref_name = format!("{}/{}", CHECKPOINT_REFS, timestamp);
+    git_stdout(&path, &["update-ref", &ref_name, &sha])?;
+
+    Ok(Checkpoint {
+        ref_name,
+        sha,
+        label,
+        timestamp: (timestamp / 1000) as i64,
+    })
+}
+
+/// List checkpoints, newest first
+#[tauri::command]
+pub fn list_checkpoints(path: String) -> Result<Vec<Checkpoint>, String> {
+    let output = git_stdout(
+        &path,
+        &[
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--format=%(refname)%09%(objectname)%09%(creatordate:unix)%09%(contents:subject)",
+            CHECKPOINT_REFS,
+        ],
+    )?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\t');
+            Some(Checkpoint {
+                ref_name: parts.next()?.to_string(),
+                sha: parts.next()?.to_string(),
+                timestamp: parts.next()?.parse().unwrap_or(0),
+                label: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Roll the repository back to a checkpoint: HEAD is reset to the commit it
+/// was taken at and its uncommitted changes are re-applied. Refuses to run
+/// over uncommitted tracked changes unless `force` is set.
+#[tauri::command]
+pub fn restore_checkpoint(path: String, ref_name: String, force: bool) -> Result<(), String> {
+    if !ref_name.starts_with(CHECKPOINT_REFS) {
+        return Err(format!("Not a checkpoint: {}", ref_name));
+    }
+
+    let dirty = git_stdout(&path, &["status", "--porcelain", "--untracked-files=no"])?;
+    if !dirty.is_empty() && !force {
+        return Err(
+            "Working tree has uncommitted changes that restoring would discard".to_string(),
+        );
+    }
+
+    let base = git_stdout(
+        &path,
+        &["rev-parse", "--verify", &format!("{}^1", ref_name)],
+    )?;
+    let changes = git_stdout(
+        &path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^2", ref_name),
+        ],
+    )
+    .ok()
+    .filter(|sha| !sha.is_empty());
+
+    git_stdout(&path, &["reset", "--hard", &base])?;
+    if let Some(changes) = changes {
+        git_stdout(&path, &["stash", "apply", "--index", &changes])?;
+    }
+
+    Ok(())
+}
+
+/// The repository's default branch: what origin/HEAD points at, else a local
+/// main/master
+pub fn default_branch(path: &str) -> Option<String> {
+    if let Ok(remote_head) = git_stdout(
+        path,
+        &[
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "refs/remotes/origin/HEAD",
+        ],
+    ) {
+        if !remote_head.is_empty() {
+            return Some(remote_head);
+        }
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|branch| {
+            git_stdout(
+                path,
+                &[
+                    "show-ref",
+                    "--verify",
+                    "--quiet",
+                    &format!("refs/heads/{}", branch),
+                ],
+            )
+            .is_ok()
+        })
+        .map(|branch| branch.to_string())
+}
+
+/// Files changed between two commits with per-file line counts
+fn diff_files(path: &str, from: &str, to: &str) -> Result<Vec<CommitFile>, String> {
+    let numstat = git_stdout(path, &["diff", "--numstat", "--no-renames", from, to])?;
+    let counts: HashMap<&str, (i32, i32)> = numstat
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            // Binary files report "-" for both counts
+            let additions = parts.next()?.parse().unwrap_or(0);
+            let deletions = parts.next()?.parse().unwrap_or(0);
+            Some((parts.next()?, (additions, deletions)))
+        })
+        .collect();
+
+    let name_status = git_stdout(path, &["diff", "--name-status", "--no-renames", from, to])?;
+    Ok(name_status
+        .lines()
+        .filter_map(|line| {
+            let (code, file) = line.split_once('\t')?;
+            let (additions, deletions) = counts.get(file).copied().unwrap_or((0, 0));
+            Some(CommitFile {
+                path: file.to_string(),
+                status: match code {
+                    "A" => "added",
+                    "D" => "deleted",
+                    _ => "modified",
+                }
+                .to_string(),
+                additions,
+                deletions,
+            })
+        })
+        .collect())
+}
+
+/// Everything committed on a worktree's branch since it diverged from its
+/// base (merge-base..HEAD). Without an explicit base ref, uses the one
+/// recorded at creation, then the default branch.
+#[tauri::command]
+pub fn worktree_branch_diff(
+    worktree_path: String,
+    base_ref: Option<String>,
+) -> Result<BranchDiff, String> {
+    let base_ref = base_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .or_else(|| recorded_base_ref(&worktree_path))
+        .or_else(|| default_branch(&worktree_path))
+        .ok_or("No base ref given and no default branch found")?;
+
+    let merge_base = git_stdout(&worktree_path, &["merge-base", &base_ref, "HEAD"])
+        .map_err(|e| format!("Failed to find merge base with {}: {}", base_ref, e))?;
+    let commit_count = git_stdout(
+        &worktree_path,
+        &["rev-list", "--count", &format!("{}..HEAD", merge_base)],
+    )?
+    .parse()
+    .unwrap_or(0);
+
+    let files = diff_files(&worktree_path, &merge_base, "HEAD")?;
+    let additions = files.iter().map(|f| f.additions).sum();
+    let deletions = files.iter().map(|f| f.deletions).sum();
+
+    Ok(BranchDiff {
+        base_ref,
+        merge_base,
+        commit_count,
+        files,
+        additions,
+        deletions,
+    })
+}
+
+fn author_initials(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let initials: String = match words.as_slice() {
+        [] => "?".to_string(),
+        [single] => single.chars().take(2).collect(),
+        [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+    };
+    initials.to_uppercase()
+}
+
+/// Distinct authors in recent history with commit counts and a stable
+/// color/initials per author, for rendering author chips offline
+#[tauri::command]
+pub fn get_author_info(
+    path: String,
+    cache: tauri::State<'_, AuthorCache>,
+) -> Result<Vec<AuthorInfo>, String> {
+    let head = git_stdout(&path, &["rev-parse", "HEAD"])?;
+    if let Some((cached_head, authors)) = cache.lock().unwrap().get(&path) {
+        if *cached_head == head {
+            return Ok(authors.clone());
+        }
+    }
+
+    let output = git_stdout(&path, &["shortlog", "-sne", AUTHOR_HISTORY_LIMIT, "HEAD"])?;
+    // Each line: <count>\t<name> <<email>>
+    let authors: Vec<AuthorInfo> = output
+        .lines()
+        .filter_map(|line| {
+            let (count, author) = line.trim().split_once('\t')?;
+            let (name, email) = author.rsplit_once(" <")?;
+            let email = email.trim_end_matches('>');
+            Some(AuthorInfo {
+                name: name.to_string(),
+                email: email.to_string(),
+                commit_count: count.trim().parse().unwrap_or(0),
+                // Keyed by email so renamed authors keep their color
+                color: hash_color(&email.to_lowercase()),
+                initials: author_initials(name),
+            })
+        })
+        .collect();
+
+    cache.lock().unwrap().insert(path, (head, authors.clone()));
+    Ok(authors)
+}
+
+#[tauri::command]
+pub fn git_stash_push(
+    path: String,
+    message: Option<String>,
+    include_untracked: bool,
+) -> Result<(), String> {
+    let mut args = vec!["stash", "push"];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+    let message = message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty());
+    if let Some(message) = &message {
+        args.extend(["-m", message]);
+    }
+
+    let output = git_stdout(&path, &args)?;
+    if output.starts_with("No local changes to save") {
+        return Err("No local changes to stash".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn git_stash_list(path: String) -> Result<Vec<StashEntry>, String> {
+    let output = git_stdout(&path, &["stash", "list", "--format=%gs"])?;
+
+    // Subjects look like "WIP on <branch>: <sha> <subject>" or "On <branch>: <message>"
+    Ok(output
+        .lines()
+        .enumerate()
+        .map(|(index, subject)| {
+            let described = subject
+                .strip_prefix("WIP on ")
+                .or_else(|| subject.strip_prefix("On "))
+                .and_then(|rest| rest.split_once(": "));
+            match described {
+                Some((branch, message)) => StashEntry {
+                    index,
+                    branch: Some(branch.to_string()),
+                    message: message.to_string(),
+                },
+                None => StashEntry {
+                    index,
+                    branch: None,
+                    message: subject.to_string(),
+                },
+            }
+        })
+        .collect())
+}
+
+/// Apply (or pop) a stash. A pop that stops on conflicts keeps the stash.
+#[tauri::command]
+pub fn git_stash_apply(path: String, index: usize, pop: bool) -> Result<(), String> {
+    let stash = format!("stash@{{{}}}", index);
+    let output = std::process::Command::new("git")
+        .args([
+            "-C",
+            &path,
+            "stash",
+            if pop { "pop" } else { "apply" },
+            &stash,
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let conflicts =
+        git_stdout(&path, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+    if !conflicts.is_empty() {
+        return Err(format!("Stash applied with conflicts in:\n{}", conflicts));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("would be overwritten") {
+        Err(
+            "Local changes would be overwritten by the stash; commit or stash them first"
+                .to_string(),
+        )
+    } else if stderr.contains("is not a valid reference") || stderr.contains("only has") {
+        Err(format!("Stash {} not found", stash))
+    } else {
+        Err(stderr.trim().to_string())
+    }
+}
+
+/// List tags, newest first
+#[tauri::command]
+pub fn list_git_tags(path: String) -> Result<Vec<TagInfo>, String> {
+    let output = git_stdout(
+        &path,
+        &[
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--format=%(refname:short)%09%(objecttype)%09%(objectname)%09%(*objectname)%09%(contents:subject)",
+            "refs/tags",
+        ],
+    )?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(5, '\t');
+            let name = parts.next()?.to_string();
+            let is_annotated = parts.next()? == "tag";
+            let object = parts.next()?;
+            let peeled = parts.next()?;
+            let subject = parts.next().unwrap_or_default();
+            Some(TagInfo {
+                name,
+                commit_hash: if is_annotated { peeled } else { object }.to_string(),
+                message: Some(subject.to_string()).filter(|_| is_annotated),
+                is_annotated,
+            })
+        })
+        .collect())
+}
+
+/// Create a tag at `target` (HEAD by default): annotated when a message is
+/// given, lightweight otherwise
+#[tauri::command]
+pub fn create_git_tag(
+    path: String,
+    name: String,
+    message: Option<String>,
+    target: Option<String>,
+) -> Result<(), String> {
+    let name = name.trim();
+    // check-ref-format accepts a leading '-', which `git tag` reads as an option
+    if name.starts_with('-') {
+        return Err(format!("Invalid tag name: {}", name));
+    }
+    let valid = std::process::Command::new("git")
+        .args(["check-ref-format", &format!("refs/tags/{}", name)])
+        .timed_status()
+        .map_err(|e| e.to_string())?
+        .success();
+    if name.is_empty() || !valid {
+        return Err(format!("Invalid tag name: {}", name));
+    }
+
+    let tag_ref = format!("refs/tags/{}", name);
+    if git_stdout(&path, &["rev-parse", "--verify", "--quiet", &tag_ref]).is_ok() {
+        return Err(format!("Tag {} already exists", name));
+    }
+
+    let mut args = vec!["tag"];
+    if let Some(message) = message.as_deref() {
+        args.extend(["-a", "-m", message]);
+    }
+    args.push(name);
+    if let Some(target) = target.as_deref().filter(|t| !t.trim().is_empty()) {
+        if target.starts_with('-') {
+            return Err(format!("Invalid target: {}", target));
+        }
+        args.push(target);
+    }
+    git_stdout(&path, &args).map(|_| ())
+}
+
+/// Contents of the repository's root .gitignore (empty if there is none)
+#[tauri::command]
+pub fn get_gitignore(path: String) -> Result<String, String> {
+    let gitignore = PathBuf::from(&path).join(".gitignore");
+    if !gitignore.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(&gitignore).map_err(|e| e.to_string())
+}
+
+/// Append a pattern to the root .gitignore. Returns false when the pattern
+/// was already listed.
+#[tauri::command]
+pub fn append_gitignore(path: String, pattern: String) -> Result<bool, String> {
+    // .gitignore patterns always use forward slashes
+    let pattern = pattern.trim().replace('\\', "/");
+    if pattern.is_empty() {
+        return Err("Pattern is empty".to_string());
+    }
+
+    let mut content = get_gitignore(path.clone())?;
+    if content.lines().any(|line| line.trim() == pattern) {
+        return Ok(false);
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&pattern);
+    content.push('\n');
+
+    fs::write(PathBuf::from(&path).join(".gitignore"), content).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignore_rule_keeps_colons_in_pattern() {
+        let check = parse_ignore_rule(".gitignore:3:*.log:old\tdebug.log:old\n");
+        assert!(check.ignored);
+        assert_eq!(check.source.as_deref(), Some(".gitignore"));
+        assert_eq!(check.line, Some(3));
+        assert_eq!(check.pattern.as_deref(), Some("*.log:old"));
+
+        let check = parse_ignore_rule(".git/info/exclude:1:!keep\tkeep\n");
+        assert!(!check.ignored);
+        assert_eq!(check.pattern.as_deref(), Some("!keep"));
+    }
+
+    #[test]
+    fn numstat_renames_map_to_new_path() {
+        assert_eq!(numstat_path("src/main.rs"), "src/main.rs");
+        assert_eq!(numstat_path("top.txt => renamed.txt"), "renamed.txt");
+        assert_eq!(numstat_path("src/{old => new}/f.rs"), "src/new/f.rs");
+        assert_eq!(numstat_path("src/{ => sub}/f.rs"), "src/sub/f.rs");
+        assert_eq!(numstat_path("src/{sub => }/f.rs"), "src/f.rs");
+        assert_eq!(numstat_path("{a.rs => b.rs}"), "b.rs");
+    }
+
+    #[test]
+    fn discard_all_in_repo_without_tracked_files() {
+        let dir = std::env::temp_dir().join(format!("aterm-discard-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string();
+        let init = std::process::Command::new("git")
+            .args(["-C", &path, "init", "-q"])
+            .status()
+            .unwrap();
+        assert!(init.success());
+        fs::write(dir.join("new.txt"), "untracked").unwrap();
+
+        let affected = discard_all_changes(path.clone(), true, false, true).unwrap();
+        assert_eq!(affected, vec!["new.txt".to_string()]);
+        assert!(!dir.join("new.txt").exists());
+
+        let affected = discard_all_changes(path, false, false, true).unwrap();
+        assert!(affected.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn fully_staged_file_has_empty_unstaged_diff() {
+        let dir = std::env::temp_dir().join(format!("aterm-diffs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-C", &path])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        fs::write(dir.join("staged.txt"), "one\n").unwrap();
+        fs::write(dir.join("new.txt"), "two\n").unwrap();
+        git(&["add", "staged.txt"]);
+
+        let diffs = get_file_diffs_both(path.clone(), "staged.txt".to_string()).unwrap();
+        assert!(diffs.staged.diff.contains("+one"));
+        assert_eq!(diffs.unstaged.diff, "");
+
+        let diffs = get_file_diffs_both(path, "new.txt".to_string()).unwrap();
+        assert_eq!(diffs.staged.diff, "");
+        assert!(diffs.unstaged.diff.starts_with("New file: new.txt"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cherry_pick_conflict_reports_files() {
+        let dir = std::env::temp_dir().join(format!("aterm-pick-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_string_lossy().to_string();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-C", &path, "-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q", "-b", "main"]);
+        fs::write(dir.join("f.txt"), "base\n").unwrap();
+        git(&["add", "f.txt"]);
+        git(&["commit", "-qm", "base"]);
+        git(&["checkout", "-qb", "other"]);
+        fs::write(dir.join("f.txt"), "other\n").unwrap();
+        git(&["commit", "-qam", "other"]);
+        git(&["checkout", "-q", "main"]);
+        fs::write(dir.join("f.txt"), "main\n").unwrap();
+        git(&["commit", "-qam", "main"]);
+
+        let err = cherry_pick_commit(path.clone(), "other".to_string()).unwrap_err();
+        assert_eq!(err.conflicts, ["f.txt"]);
+        assert_eq!(err.in_progress.as_deref(), Some("cherry-pick"));
+
+        let err = revert_commit(path, "HEAD".to_string(), false).unwrap_err();
+        assert_eq!(err.in_progress.as_deref(), Some("cherry-pick"));
+        assert_eq!(err.conflicts, ["f.txt"]);
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
@@ -1,34 +1,85 @@
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 use tauri::menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 
+mod ansi;
 mod config;
 mod directory;
+mod editorconfig;
 mod file_ops;
 mod git;
+mod git_timing;
 mod iterm;
 mod notify;
+mod process;
+mod project;
 mod pty;
+mod replay;
+mod scrollback;
+mod session;
+mod terminal_import;
+mod watcher;
 mod window;
 mod worktree;
 
-use config::{load_config, save_config};
-use directory::{get_home_dir, list_all_project_files, list_directory, list_project_directory};
-use file_ops::{open_in_editor, read_file_content, write_file_content};
+use crate::notify::{
+    get_notification_permission_status, request_notification_permission, send_bell_notification,
+};
+use ansi::strip_ansi_escapes;
+use config::{diff_config_keys, load_config, load_config_versioned, save_config};
+use directory::{
+    complete_path, get_directory_size, get_file_search_options, get_home_dir, get_ignored_dirs,
+    list_all_project_files, list_directory, list_project_directory, resolve_start_directory,
+    save_file_search_options, search_file_contents, search_project_files,
+};
+use editorconfig::get_editorconfig;
+use file_ops::{
+    create_directory, create_file, delete_path, get_file_preview, open_in_editor,
+    read_file_content, read_file_content_capped, rename_path, should_open_in_editor,
+    write_file_content,
+};
 use git::{
-    clone_repo, discard_changes, get_commit_diff, get_commit_files, get_commit_history,
-    get_file_diff, get_git_remote, get_git_status, git_commit, git_push, stage_all, stage_files,
-    unstage_all, unstage_files,
+    add_git_remote, append_gitignore, cancel_clone, check_ignored, check_self_update,
+    cherry_pick_commit, clone_repo, create_checkpoint, create_git_tag, detect_remote_provider,
+    discard_all_changes, discard_changes, get_author_info, get_commit_diff, get_commit_files,
+    get_commit_history, get_commit_permalink, get_commits_files, get_file_diff,
+    get_file_diff_structured, get_file_diffs_both, get_git_attributes, get_git_remote,
+    get_git_status, get_gitignore, get_gutter_diff, get_repo_title_summary, get_status_counts,
+    git_blame, git_commit, git_commit_amend, git_fetch, git_gc, git_pull, git_push, git_reset,
+    git_stash_apply, git_stash_list, git_stash_push, is_at_ref, list_checkpoints, list_git_remotes,
+    list_git_tags, precommit_summary, resolve_ref, restore_checkpoint, restore_file_to,
+    revert_commit, set_remote_url, stage_all, stage_files, track_remote_branch, unstage_all,
+    unstage_files, worktree_branch_diff, AuthorCache, CloneMap,
 };
+use git_timing::{get_git_timings, set_git_timings_enabled};
 use iterm::get_iterm_profiles;
+use process::run_and_capture_head;
+use project::open_project_context;
 use pty::{
-    force_exit, get_active_pty_count, kill_all_ptys, kill_pty, resize_pty, spawn_pty, write_pty,
-    PtyMap,
+    export_pty_buffer_html, force_exit, get_active_pty_count, get_command_marks,
+    get_last_command_output, get_pty_info, get_pty_scrollback, get_pty_scrollback_usage,
+    kill_all_ptys, kill_pty, pty_cd, reap_dead_ptys, reattach_pty, resize_pty, resize_ptys,
+    set_pty_scrollback_limit, set_pty_title, signal_pty, spawn_git_terminal, spawn_pty,
+    spawn_pty_with_script, write_pty, write_pty_broadcast, PtyMap,
+};
+use replay::{pause_replay, replay_cast, resume_replay, stop_replay, ReplayMap};
+use session::{forget_pane_session, record_pane_session, recover_sessions};
+use terminal_import::{get_alacritty_config, get_wezterm_config};
+use watcher::{
+    unwatch_git_status, unwatch_project, watch_git_status, watch_project, GitWatcherMap,
+    ProjectWatcherMap,
+};
+use window::{
+    close_detached_window, create_detached_window, list_detached_windows, list_monitors,
+    restore_window_layout, save_main_window_state, save_window_layout,
+};
+use worktree::{
+    branch_color, branch_worktree, checkout_branch, create_worktree, get_ssh_agent_status,
+    list_aterm_branches, list_git_branches, list_remote_tmux, list_ssh_keys, list_worktrees,
+    prune_worktrees, remove_worktree, remove_worktrees, rename_worktree, resolve_ssh_host,
+    sync_preserved_files, validate_worktree_base,
 };
-use notify::send_bell_notification;
-use window::{close_detached_window, create_detached_window, list_detached_windows};
-use worktree::{create_worktree, list_git_branches, list_worktrees, remove_worktree};
 
 // ============================================================================
 // App Entry Point
@@ -36,52 +87,174 @@ use worktree::{create_worktree, list_git_branches, list_worktrees, remove_worktr
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let pty_map: PtyMap = Arc::new(Mutex::new(HashMap::new()));
+    let pty_map: PtyMap = Arc::new(RwLock::new(HashMap::new()));
+    let git_watchers: GitWatcherMap = Arc::new(Mutex::new(HashMap::new()));
+    let project_watchers: ProjectWatcherMap = Arc::new(Mutex::new(HashMap::new()));
+    let replays: ReplayMap = Arc::new(Mutex::new(HashMap::new()));
+    let author_cache: AuthorCache = Arc::new(Mutex::new(HashMap::new()));
+    let clones: CloneMap = Arc::new(Mutex::new(HashMap::new()));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(pty_map)
+        .manage(git_watchers)
+        .manage(project_watchers)
+        .manage(replays)
+        .manage(author_cache)
+        .manage(clones)
         .invoke_handler(tauri::generate_handler![
             load_config,
+            load_config_versioned,
             save_config,
+            diff_config_keys,
+            open_project_context,
             list_directory,
             list_project_directory,
             list_all_project_files,
+            search_project_files,
+            search_file_contents,
+            get_file_search_options,
+            save_file_search_options,
+            get_directory_size,
+            get_ignored_dirs,
             get_home_dir,
+            resolve_start_directory,
+            complete_path,
             clone_repo,
+            cancel_clone,
             get_git_remote,
+            list_git_remotes,
+            add_git_remote,
+            set_remote_url,
             get_git_status,
+            get_repo_title_summary,
+            get_status_counts,
             get_file_diff,
+            get_file_diff_structured,
+            get_file_diffs_both,
+            get_git_attributes,
+            get_gutter_diff,
+            watch_git_status,
+            unwatch_git_status,
+            watch_project,
+            unwatch_project,
             stage_files,
+            check_ignored,
+            get_gitignore,
+            append_gitignore,
             stage_all,
             unstage_files,
             unstage_all,
             discard_changes,
+            discard_all_changes,
+            precommit_summary,
             git_commit,
+            git_commit_amend,
             git_push,
+            git_fetch,
+            git_pull,
+            git_stash_push,
+            git_stash_list,
+            git_stash_apply,
+            list_git_tags,
+            create_git_tag,
+            git_gc,
+            track_remote_branch,
+            check_self_update,
+            resolve_ref,
+            is_at_ref,
+            get_commit_permalink,
+            create_checkpoint,
+            list_checkpoints,
+            restore_checkpoint,
+            detect_remote_provider,
             get_commit_history,
+            git_blame,
+            get_author_info,
             get_commit_files,
+            get_commits_files,
             get_commit_diff,
+            cherry_pick_commit,
+            revert_commit,
+            git_reset,
+            restore_file_to,
+            get_git_timings,
+            set_git_timings_enabled,
             open_in_editor,
             read_file_content,
+            get_editorconfig,
+            read_file_content_capped,
             write_file_content,
+            create_file,
+            create_directory,
+            rename_path,
+            delete_path,
+            should_open_in_editor,
+            get_file_preview,
             get_iterm_profiles,
+            get_alacritty_config,
+            get_wezterm_config,
+            record_pane_session,
+            forget_pane_session,
+            recover_sessions,
+            run_and_capture_head,
             create_worktree,
             remove_worktree,
+            prune_worktrees,
+            remove_worktrees,
+            sync_preserved_files,
+            rename_worktree,
+            worktree_branch_diff,
+            validate_worktree_base,
+            resolve_ssh_host,
+            list_ssh_keys,
+            get_ssh_agent_status,
+            list_remote_tmux,
             list_worktrees,
             list_git_branches,
+            checkout_branch,
+            list_aterm_branches,
+            branch_worktree,
+            branch_color,
             spawn_pty,
+            spawn_pty_with_script,
+            spawn_git_terminal,
             write_pty,
+            write_pty_broadcast,
+            pty_cd,
+            export_pty_buffer_html,
+            set_pty_scrollback_limit,
+            get_pty_scrollback,
+            get_command_marks,
+            get_last_command_output,
+            get_pty_scrollback_usage,
+            replay_cast,
+            pause_replay,
+            resume_replay,
+            stop_replay,
             resize_pty,
+            resize_ptys,
             kill_pty,
+            signal_pty,
             get_active_pty_count,
             kill_all_ptys,
             force_exit,
+            reap_dead_ptys,
+            set_pty_title,
+            get_pty_info,
+            reattach_pty,
             create_detached_window,
             close_detached_window,
             list_detached_windows,
+            list_monitors,
+            save_main_window_state,
+            save_window_layout,
+            restore_window_layout,
             send_bell_notification,
+            get_notification_permission_status,
+            request_notification_permission,
+            strip_ansi_escapes,
         ])
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
@@ -95,6 +268,15 @@ pub fn run() {
                 )?;
             }
 
+            // Restore main window size/position from the last session
+            window::restore_main_window_state(app.handle());
+
+            // Record git command timings if debugGitTimings is set
+            git_timing::init_git_timings();
+
+            // Clean up PTYs whose shells exited without the frontend noticing
+            pty::spawn_pty_reaper(app.handle().clone());
+
             // Initialize notifications (click-to-focus via user-notify)
             notify::init_notifications(app.handle());
 
@@ -150,10 +332,22 @@ pub fn run() {
 
             Ok(())
         })
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+                if window.label() == "main" =>
+            {
+                window::schedule_main_window_state_save(window.app_handle());
+            }
+            tauri::WindowEvent::CloseRequested { api, .. } => {
                 // Only show confirmation dialog for main window
                 if window.label() == "main" {
+                    // Remember geometry for the next launch
+                    if let Err(e) = window::store_main_window_state(window.app_handle()) {
+                        log::warn!("Failed to save main window state: {}", e);
+                    }
+                    if let Err(e) = window::store_window_layout(window.app_handle()) {
+                        log::warn!("Failed to save window layout: {}", e);
+                    }
                     // Prevent default close behavior
                     api.prevent_close();
                     // Emit event to frontend to show confirmation dialog
@@ -161,6 +355,7 @@ pub fn run() {
                 }
                 // Detached windows close normally without confirmation
             }
+            _ => {}
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
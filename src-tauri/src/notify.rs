@@ -1,6 +1,36 @@
+use crate::config::{load_state_file, save_state_file};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
-use user_notify::{NotificationResponseAction, get_notification_manager};
+use tauri::{AppHandle, Emitter, Manager};
+use user_notify::{
+    NotificationCategory, NotificationCategoryAction, NotificationResponseAction,
+    get_notification_manager,
+};
+
+// Category for notifications tied to a pane. Its buttons only show on macOS;
+// elsewhere the category is ignored and clicking the notification still
+// focuses the pane.
+const PANE_CATEGORY: &str = "aterm.pane";
+const FOCUS_PANE_ACTION: &str = "aterm.pane.focus";
+const DISMISS_ACTION: &str = "aterm.pane.dismiss";
+const PANE_ID_KEY: &str = "paneId";
+
+// File holding the answer to the last permission request, so a refusal can
+// be told apart from never having asked
+const PERMISSION_FILE: &str = "notification-permission.json";
+
+// Set while a permission request is waiting on the user
+static ASKING_PERMISSION: AtomicBool = AtomicBool::new(false);
+
+// Clears ASKING_PERMISSION even if the request future is dropped mid-await
+struct AskingGuard;
+
+impl Drop for AskingGuard {
+    fn drop(&mut self) {
+        ASKING_PERMISSION.store(false, Ordering::SeqCst);
+    }
+}
 
 pub fn init_notifications(app: &AppHandle) {
     let bundle_id = app
@@ -10,20 +40,44 @@ pub fn init_notifications(app: &AppHandle) {
 
     let manager = get_notification_manager(bundle_id, None);
 
+    let pane_category = NotificationCategory {
+        identifier: PANE_CATEGORY.to_string(),
+        actions: vec![
+            NotificationCategoryAction::Action {
+                identifier: FOCUS_PANE_ACTION.to_string(),
+                title: "Focus pane".to_string(),
+            },
+            NotificationCategoryAction::Action {
+                identifier: DISMISS_ACTION.to_string(),
+                title: "Dismiss".to_string(),
+            },
+        ],
+    };
+
     let app_handle = app.clone();
     manager
         .register(
             Box::new(move |response| {
                 log::info!("[notify] response: {:?}", response.action);
-                if response.action == NotificationResponseAction::Default {
-                    // User clicked the notification — bring window to focus
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+                let focus = match &response.action {
+                    NotificationResponseAction::Default => true,
+                    NotificationResponseAction::Other(action) => action == FOCUS_PANE_ACTION,
+                    NotificationResponseAction::Dismiss => false,
+                };
+                if !focus {
+                    return;
+                }
+
+                // Bring the window to focus, and the pane too if there is one
+                if let Some(window) = app_handle.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+                if let Some(pane_id) = response.user_info.get(PANE_ID_KEY) {
+                    let _ = app_handle.emit("focus-pane", pane_id);
                 }
             }),
-            vec![], // No custom action categories needed
+            vec![pane_category],
         )
         .unwrap_or_else(|e| log::error!("[notify] register failed: {e}"));
 
@@ -31,10 +85,7 @@ pub fn init_notifications(app: &AppHandle) {
     {
         let manager_clone = manager.clone();
         tauri::async_runtime::spawn(async move {
-            if let Err(e) = manager_clone
-                .first_time_ask_for_notification_permission()
-                .await
-            {
+            if let Err(e) = ask_permission(&manager_clone).await {
                 log::warn!("[notify] permission request failed: {e}");
             }
         });
@@ -51,10 +102,16 @@ pub async fn send_bell_notification(
     state: tauri::State<'_, NotifyState>,
     title: String,
     body: String,
+    pane_id: Option<String>,
 ) -> Result<(), String> {
-    let notification = user_notify::NotificationBuilder::new()
+    let mut notification = user_notify::NotificationBuilder::new()
         .title(&title)
         .body(&body);
+    if let Some(pane_id) = pane_id {
+        notification = notification
+            .set_category_id(PANE_CATEGORY)
+            .set_user_info(HashMap::from([(PANE_ID_KEY.to_string(), pane_id)]));
+    }
 
     state
         .0
@@ -64,3 +121,71 @@ pub async fn send_bell_notification(
 
     Ok(())
 }
+
+fn save_permission(status: &str) {
+    if let Err(e) = save_state_file(PERMISSION_FILE, &status) {
+        log::warn!("[notify] failed to save permission status: {e}");
+    }
+}
+
+/// Ask for permission and record the answer. macOS only shows the prompt
+/// the first time; after that it just returns the earlier answer.
+async fn ask_permission(
+    manager: &Arc<dyn user_notify::NotificationManager>,
+) -> Result<bool, String> {
+    if ASKING_PERMISSION.swap(true, Ordering::SeqCst) {
+        return Err("A notification permission request is already pending".to_string());
+    }
+    let _guard = AskingGuard;
+
+    let granted = manager
+        .first_time_ask_for_notification_permission()
+        .await
+        .map_err(|e| format!("{e}"))?;
+    save_permission(if granted { "authorized" } else { "denied" });
+    Ok(granted)
+}
+
+/// "authorized", "denied", or "notDetermined". The OS only reports whether
+/// notifications are allowed, so "denied" means we asked and they aren't.
+async fn permission_status(
+    manager: &Arc<dyn user_notify::NotificationManager>,
+) -> Result<&'static str, String> {
+    let authorized = manager
+        .get_notification_permission_state()
+        .await
+        .map_err(|e| format!("{e}"))?;
+    let asked = load_state_file::<String>(PERMISSION_FILE).is_some();
+    Ok(match (authorized, asked) {
+        (true, _) => "authorized",
+        (false, true) => "denied",
+        (false, false) => "notDetermined",
+    })
+}
+
+#[tauri::command]
+pub async fn get_notification_permission_status(
+    state: tauri::State<'_, NotifyState>,
+) -> Result<String, String> {
+    permission_status(&state.0).await.map(String::from)
+}
+
+/// Ask for notification permission again, returning the resulting status.
+/// Since macOS won't prompt again after a refusal, an app that's already
+/// denied opens the Notifications page of System Settings instead.
+#[tauri::command]
+pub async fn request_notification_permission(
+    state: tauri::State<'_, NotifyState>,
+) -> Result<String, String> {
+    if permission_status(&state.0).await? == "denied" {
+        #[cfg(target_os = "macos")]
+        std::process::Command::new("open")
+            .arg("x-apple.systempreferences:com.apple.preference.notifications")
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        return Ok("denied".to_string());
+    }
+
+    let granted = ask_permission(&state.0).await?;
+    Ok(if granted { "authorized" } else { "denied" }.to_string())
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long a quick command may run before it's killed
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Hard cap on captured stdout, so one huge line can't balloon memory either
+const MAX_CAPTURE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedHead {
+    lines: Vec<String>,
+    exit_code: Option<i32>, // None when killed (line limit or timeout) or by a signal
+    truncated: bool,        // Stopped reading at max_lines
+    timed_out: bool,
+}
+
+fn capture_head(
+    program: &str,
+    args: &[String],
+    cwd: Option<&str>,
+    max_lines: usize,
+    timeout: Duration,
+) -> Result<CapturedHead, String> {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let (done_tx, done_rx) = mpsc::channel();
+    let reader_lines = lines.clone();
+    thread::spawn(move || {
+        let reader = BufReader::new(stdout.take(MAX_CAPTURE_BYTES));
+        for line in reader.lines() {
+            let Ok(line) = line else { break };
+            let mut lines = reader_lines.lock().unwrap();
+            if lines.len() >= max_lines {
+                let _ = done_tx.send(true);
+                return;
+            }
+            lines.push(line);
+        }
+        let _ = done_tx.send(false);
+    });
+
+    let deadline = Instant::now() + timeout;
+    let (truncated, mut timed_out) = match done_rx.recv_timeout(timeout) {
+        Ok(truncated) => (truncated, false),
+        Err(_) => (false, true),
+    };
+
+    // Output is complete; give the process the rest of the timeout to exit
+    let mut exit_code = None;
+    if !truncated && !timed_out {
+        loop {
+            match child.try_wait().map_err(|e| e.to_string())? {
+                Some(status) => {
+                    exit_code = status.code();
+                    break;
+                }
+                None if Instant::now() >= deadline => {
+                    timed_out = true;
+                    break;
+                }
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+    }
+    if exit_code.is_none() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    let lines = std::mem::take(&mut *lines.lock().unwrap());
+    Ok(CapturedHead {
+        lines,
+        exit_code,
+        truncated,
+        timed_out,
+    })
+}
+
+/// Run a short command without a PTY and return at most `max_lines` of its
+/// stdout, killing it once enough output was read or the timeout passed.
+/// Meant for version badges and quick diagnostics.
+#[tauri::command]
+pub async fn run_and_capture_head(
+    program: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    max_lines: usize,
+    timeout_ms: Option<u64>,
+) -> Result<CapturedHead, String> {
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT);
+    tauri::async_runtime::spawn_blocking(move || {
+        capture_head(&program, &args, cwd.as_deref(), max_lines, timeout)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
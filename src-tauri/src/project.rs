@@ -0,0 +1,42 @@
+use crate::directory::{list_project_directory, ProjectFileEntry};
+use crate::git::{get_git_remote, get_git_status, GitStatus};
+use crate::worktree::{list_worktrees, WorktreeInfo};
+use serde::Serialize;
+use std::thread;
+
+// Everything the UI loads when a project is opened
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectContext {
+    status: Option<GitStatus>, // None outside a git repository
+    remote: Option<String>,
+    entries: Vec<ProjectFileEntry>, // Top-level directory listing
+    worktrees: Vec<WorktreeInfo>,
+}
+
+/// Gather git status, remote, top-level listing, and worktrees for a project
+/// in one call, running the lookups concurrently
+#[tauri::command]
+pub async fn open_project_context(path: String) -> Result<ProjectContext, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        thread::scope(|scope| {
+            let status = scope.spawn(|| get_git_status(path.clone()).ok());
+            let remote = scope.spawn(|| get_git_remote(path.clone()).ok().flatten());
+            let worktrees = scope.spawn(|| list_worktrees(path.clone(), None).unwrap_or_default());
+            let entries = list_project_directory(path.clone(), None, None)?;
+
+            // get_git_status doesn't fail outside a repo, but listing
+            // worktrees does, and any repo has at least its main worktree
+            let worktrees = worktrees.join().unwrap_or_default();
+            let status = status.join().unwrap_or(None);
+            Ok(ProjectContext {
+                status: status.filter(|_| !worktrees.is_empty()),
+                remote: remote.join().unwrap_or(None),
+                entries,
+                worktrees,
+            })
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -1,28 +1,299 @@
+use crate::ansi::{ansi_to_html, strip_ansi, PromptMark, PromptMarkScanner};
+use crate::config::load_config;
+use crate::scrollback::{default_scrollback_limit, Scrollback};
+use crate::session::{save_pane_title, saved_pane_title};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, RwLock, Weak};
 use std::thread;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
 
-pub type PtyMap = Arc<Mutex<HashMap<String, PtyHandle>>>;
+// The map lock is only held to look up, insert, or remove a handle; work on
+// a PTY happens under that PTY's own lock
+pub type PtyMap = Arc<RwLock<HashMap<String, Arc<Mutex<PtyHandle>>>>>;
+
+// Locked separately from its handle so a blocking write doesn't hold up
+// resizes, signals, or the reaper for that pane
+type PtyWriter = Arc<Mutex<Box<dyn Write + Send>>>;
 
 pub struct PtyHandle {
     master: Box<dyn portable_pty::MasterPty + Send>,
-    writer: Box<dyn Write + Send>,
+    writer: PtyWriter,
     child: Box<dyn portable_pty::Child + Send>,
+    scrollback: Arc<Mutex<Scrollback>>,
+    // User-assigned name; takes precedence over OSC-driven titles
+    title: Option<String>,
+    // OSC 133 command boundaries, oldest first
+    command_marks: Arc<Mutex<Vec<PromptMark>>>,
+    // Locked while a batch is emitted so a reattach replay can't interleave
+    // with live output
+    emitted: Arc<Mutex<EmitCursor>>,
+}
+
+// How far a pane's output has been delivered, as output stream offsets
+#[derive(Debug, Default)]
+struct EmitCursor {
+    emitted: usize,  // End of the last live batch
+    replayed: usize, // End of the last replay; live bytes before it are skipped
+}
+
+impl EmitCursor {
+    fn delivered(&self) -> usize {
+        self.emitted.max(self.replayed)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PtyResize {
+    id: String,
+    cols: u16,
+    rows: u16,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyInfo {
+    id: String,
+    title: Option<String>,
+    pid: Option<u32>,
+    last_seq: usize, // Output stream offset delivered so far
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyReplay {
+    start: usize, // Output stream offsets the replayed bytes cover
+    end: usize,
+    data: String, // Base64, like `pty-output` payloads
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutput {
+    start: usize, // Output stream offsets of the command's output
+    end: usize,
+    text: String, // Output with escape sequences stripped
+    exit_code: Option<i32>,
+}
+
+// Payload of `pty-write-blocked-{id}` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyWriteBlocked {
+    elapsed_ms: u64,
+    bytes: usize,
+}
+
+// Payload of `pty-exit-{id}` events
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyExit {
+    code: u32,
+    signal: Option<String>,
+}
+
+impl From<&portable_pty::ExitStatus> for PtyExit {
+    fn from(status: &portable_pty::ExitStatus) -> Self {
+        let description = status.to_string();
+        PtyExit {
+            code: status.exit_code(),
+            signal: description
+                .strip_prefix("Terminated by ")
+                .map(|s| s.to_string()),
+        }
+    }
+}
+
+/// Check that a user-chosen shell exists and can be executed
+fn validate_shell(shell: &str) -> Result<(), String> {
+    let metadata = std::fs::metadata(shell).map_err(|_| format!("Shell not found: {}", shell))?;
+    if !metadata.is_file() {
+        return Err(format!("Shell is not a file: {}", shell));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("Shell is not executable: {}", shell));
+        }
+    }
+    Ok(())
+}
+
+// Command marks kept per pane; the oldest are dropped beyond this
+const MAX_COMMAND_MARKS: usize = 1000;
+
+// How often the background reaper checks for exited PTYs
+const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+// Output is emitted as soon as a batch reaches this size, timer or not
+const OUTPUT_BATCH_BYTES: usize = 128 * 1024;
+
+// How long output is held to coalesce reads, unless configured otherwise
+const DEFAULT_OUTPUT_FLUSH_MS: u64 = 8;
+
+/// Batching window from the `ptyOutputFlushMs` config key. 0 still merges
+/// reads that are already queued but never waits for more.
+fn output_flush_interval() -> Duration {
+    let millis = load_config()
+        .ok()
+        .and_then(|config| config.get("ptyOutputFlushMs")?.as_u64())
+        .unwrap_or(DEFAULT_OUTPUT_FLUSH_MS);
+    Duration::from_millis(millis)
+}
+
+/// Coalesce PTY reads into one `pty-output` event per batch, flushing when
+/// the batch is full or `interval` has passed since its first read. Returns
+/// once the reader hangs up and everything queued has been emitted.
+fn emit_batched_output(
+    app: &AppHandle,
+    event: &str,
+    rx: Receiver<Vec<u8>>,
+    interval: Duration,
+    emitted: &Mutex<EmitCursor>,
+) {
+    let mut batch = Vec::new();
+    while let Ok(chunk) = rx.recv() {
+        batch.extend_from_slice(&chunk);
+        let deadline = Instant::now() + interval;
+        while batch.len() < OUTPUT_BATCH_BYTES {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(chunk) => batch.extend_from_slice(&chunk),
+                // On hang-up the outer recv ends the loop after this flush
+                Err(_) => break,
+            }
+        }
+
+        // Encode as base64 - much more efficient than JSON array
+        // JSON array: [72,101,108,108,111] = ~20 bytes for "Hello"
+        // Base64: "SGVsbG8=" = 8 bytes for "Hello"
+        let mut cursor = emitted.lock().unwrap();
+        // Skip what a reattach already replayed
+        let skip = cursor
+            .replayed
+            .saturating_sub(cursor.emitted)
+            .min(batch.len());
+        if skip < batch.len() {
+            let _ = app.emit(event, BASE64.encode(&batch[skip..]));
+        }
+        cursor.emitted += batch.len();
+        drop(cursor);
+        batch.clear();
+    }
+}
+
+// How long the cwd may take to stat before it's treated as a slow mount
+const CWD_STAT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Stat the cwd on a helper thread so an unreachable network mount can't
+/// hang the spawn. On timeout, returns an error prefixed with
+/// `SlowFilesystem:` so the frontend can offer to retry with
+/// `allow_slow_cwd`. The stuck stat thread is left to finish on its own.
+fn check_cwd_responsive(cwd: &str) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let path = cwd.to_string();
+    thread::spawn(move || {
+        let _ = std::fs::metadata(&path);
+        let _ = tx.send(());
+    });
+
+    match rx.recv_timeout(CWD_STAT_TIMEOUT) {
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(format!(
+            "SlowFilesystem: {} did not respond within {}ms; it may be on a slow or unreachable network mount",
+            cwd,
+            CWD_STAT_TIMEOUT.as_millis()
+        )),
+        // Missing directories are reported by the spawn itself
+        _ => Ok(()),
+    }
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_pty(
     id: String,
     cwd: String,
     cols: u16,
     rows: u16,
     command: Option<String>,
+    title: Option<String>,
+    shell: Option<String>,
+    extra_args: Option<Vec<String>>,
+    scrollback_bytes: Option<usize>,
+    allow_slow_cwd: Option<bool>,
+    env: Option<HashMap<String, String>>,
     app: AppHandle,
     state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    if !allow_slow_cwd.unwrap_or(false) {
+        check_cwd_responsive(&cwd)?;
+    }
+
+    let shell = match shell.filter(|s| !s.trim().is_empty()) {
+        Some(shell) => {
+            validate_shell(&shell)?;
+            shell
+        }
+        None => std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string()),
+    };
+    let extra_args = extra_args.unwrap_or_default();
+
+    // Long options (e.g. bash's --norc) must come before single-character ones
+    let mut cmd = CommandBuilder::new(&shell);
+    cmd.args(&extra_args);
+    cmd.args(["-l", "-i"]);
+    if let Some(ref command) = command {
+        // Run command, then exec a new shell when it exits
+        let relaunch: Vec<String> = std::iter::once(&shell)
+            .chain(&extra_args)
+            .map(|a| shell_quote(a))
+            .collect();
+        cmd.args([
+            "-c",
+            &format!("{}; exec {} -l -i", command, relaunch.join(" ")),
+        ]);
+    }
+    cmd.cwd(&cwd);
+    cmd.env("TERM", "xterm-256color");
+    // Added on top of the inherited environment and may override TERM.
+    // Empty values are set as empty, not unset.
+    for (key, value) in env.unwrap_or_default() {
+        cmd.env(key, value);
+    }
+
+    // Without an explicit title, restore the one the user gave this pane
+    let title = title
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| saved_pane_title(&id));
+    start_pty(
+        id,
+        cmd,
+        cols,
+        rows,
+        title,
+        scrollback_bytes,
+        app,
+        state.inner(),
+    )
+}
+
+/// Run `cmd` in a new PTY registered under `id`, streaming its output as
+/// `pty-output-{id}` and reporting its exit as `pty-exit-{id}`
+#[allow(clippy::too_many_arguments)]
+fn start_pty(
+    id: String,
+    cmd: CommandBuilder,
+    cols: u16,
+    rows: u16,
+    title: Option<String>,
+    scrollback_bytes: Option<usize>,
+    app: AppHandle,
+    state: &PtyMap,
 ) -> Result<(), String> {
     let pty_system = native_pty_system();
 
@@ -35,74 +306,477 @@ pub fn spawn_pty(
         })
         .map_err(|e| e.to_string())?;
 
-    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-
-    let mut cmd = if let Some(ref command) = command {
-        // Run command, then exec a new shell when it exits
-        let mut c = CommandBuilder::new(&shell);
-        c.args([
-            "-l",
-            "-i",
-            "-c",
-            &format!("{}; exec {} -l -i", command, shell),
-        ]);
-        c
-    } else {
-        let mut c = CommandBuilder::new(&shell);
-        c.args(["-l", "-i"]);
-        c
-    };
-    cmd.cwd(&cwd);
-    cmd.env("TERM", "xterm-256color");
-
     let child = pair.slave.spawn_command(cmd).map_err(|e| e.to_string())?;
 
     let mut reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
     let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
+    // 0 disables retention; None uses the configured default
+    let scrollback_limit = scrollback_bytes.unwrap_or_else(default_scrollback_limit);
+    let scrollback = Arc::new(Mutex::new(Scrollback::new(scrollback_limit)));
+    let command_marks = Arc::new(Mutex::new(Vec::new()));
+    let emitted = Arc::new(Mutex::new(EmitCursor::default()));
 
-    {
-        let mut ptys = state.lock().unwrap();
-        ptys.insert(
-            id.clone(),
-            PtyHandle {
-                master: pair.master,
-                writer,
-                child,
-            },
-        );
-    }
+    let handle = Arc::new(Mutex::new(PtyHandle {
+        master: pair.master,
+        writer: Arc::new(Mutex::new(writer)),
+        child,
+        scrollback: scrollback.clone(),
+        title: title.filter(|t| !t.trim().is_empty()),
+        command_marks: command_marks.clone(),
+        emitted: emitted.clone(),
+    }));
+    // Held weakly so the reader thread doesn't keep a killed pane alive
+    let own_handle = Arc::downgrade(&handle);
+    state.write().unwrap().insert(id.clone(), handle);
+
+    let (output_tx, output_rx) = mpsc::channel::<Vec<u8>>();
+    let emitter = {
+        let app = app.clone();
+        let event = format!("pty-output-{}", id);
+        let interval = output_flush_interval();
+        thread::spawn(move || emit_batched_output(&app, &event, output_rx, interval, &emitted))
+    };
 
     let event_id = id.clone();
+    let ptys = state.clone();
     thread::spawn(move || {
         // 64KB buffer for better throughput on fast output
         let mut buf = [0u8; 65536];
+        let mut prompt_scanner = PromptMarkScanner::default();
         loop {
             match reader.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => {
-                    // Encode as base64 - much more efficient than JSON array
-                    // JSON array: [72,101,108,108,111] = ~20 bytes for "Hello"
-                    // Base64: "SGVsbG8=" = 8 bytes for "Hello"
-                    let encoded = BASE64.encode(&buf[..n]);
-                    let _ = app.emit(&format!("pty-output-{}", event_id), encoded);
+                    scrollback.lock().unwrap().push(&buf[..n]);
+                    for mark in prompt_scanner.scan(&buf[..n]) {
+                        let _ = app.emit(&format!("pty-prompt-{}", event_id), &mark);
+                        let mut marks = command_marks.lock().unwrap();
+                        if marks.len() >= MAX_COMMAND_MARKS {
+                            marks.remove(0);
+                        }
+                        marks.push(mark);
+                    }
+                    let _ = output_tx.send(buf[..n].to_vec());
                 }
                 Err(_) => break,
             }
         }
+
+        // Let the emitter flush whatever is still batched before reporting exit
+        drop(output_tx);
+        let _ = emitter.join();
+
+        // EOF: the shell exited. Unless the pane was already killed, reaped,
+        // or replaced by a respawn under the same id, report how it ended.
+        let handle = {
+            let mut ptys = ptys.write().unwrap();
+            match ptys.get(&event_id) {
+                Some(pty) if Weak::ptr_eq(&own_handle, &Arc::downgrade(pty)) => {
+                    ptys.remove(&event_id)
+                }
+                _ => None,
+            }
+        };
+        if let Some(handle) = handle {
+            if let Ok(status) = handle.lock().unwrap().child.wait() {
+                let _ = app.emit(&format!("pty-exit-{}", event_id), PtyExit::from(&status));
+            }
+        }
     });
 
     Ok(())
 }
 
+/// Open a pane running `git <git_args>` in the repository, for flows that
+/// need a real terminal (`rebase -i`, `add -p`, ...). git runs under the
+/// user's login shell so it sees their PATH and EDITOR. The pane closes when
+/// git exits, reported as usual via `pty-exit-{id}`.
 #[tauri::command]
-pub fn write_pty(id: String, data: String, state: tauri::State<'_, PtyMap>) -> Result<(), String> {
-    let mut ptys = state.lock().unwrap();
-    if let Some(pty) = ptys.get_mut(&id) {
-        pty.writer
-            .write_all(data.as_bytes())
-            .map_err(|e| e.to_string())?;
-        pty.writer.flush().map_err(|e| e.to_string())?;
+pub fn spawn_git_terminal(
+    id: String,
+    path: String,
+    git_args: Vec<String>,
+    cols: u16,
+    rows: u16,
+    app: AppHandle,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    if git_args.is_empty() {
+        return Err("No git command given".to_string());
+    }
+    check_cwd_responsive(&path)?;
+    let repo = std::path::Path::new(&path);
+    if !repo.is_dir() {
+        return Err(format!("Directory does not exist: {}", path));
+    }
+    // A .git directory, or a .git file for worktrees and submodules
+    if !repo.ancestors().any(|dir| dir.join(".git").exists()) {
+        return Err(format!("Not a git repository: {}", path));
+    }
+
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+    let quoted: Vec<String> = git_args.iter().map(|a| shell_quote(a)).collect();
+    let mut cmd = CommandBuilder::new(&shell);
+    cmd.args(["-l", "-i", "-c", &format!("exec git {}", quoted.join(" "))]);
+    cmd.cwd(&path);
+    cmd.env("TERM", "xterm-256color");
+
+    let title = format!("git {}", git_args.join(" "));
+    start_pty(id, cmd, cols, rows, Some(title), None, app, state.inner())
+}
+
+// Writes that take longer than this are reported as backpressure
+const WRITE_BLOCKED_THRESHOLD: Duration = Duration::from_millis(200);
+
+/// Look up a PTY's handle, holding the map lock only for the lookup
+fn get_pty(state: &PtyMap, id: &str) -> Result<Arc<Mutex<PtyHandle>>, String> {
+    state
+        .read()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("PTY not found: {}", id))
+}
+
+fn pty_writer(state: &PtyMap, id: &str) -> Option<PtyWriter> {
+    let handle = get_pty(state, id).ok()?;
+    let writer = handle.lock().unwrap().writer.clone();
+    Some(writer)
+}
+
+/// Write and flush holding only this pane's writer lock, returning how long
+/// it took. `write_all` retries interrupted and partial writes.
+fn timed_write(writer: &PtyWriter, data: &[u8]) -> (std::io::Result<()>, Duration) {
+    let started = Instant::now();
+    let result = {
+        let mut writer = writer.lock().unwrap();
+        writer.write_all(data).and_then(|_| writer.flush())
+    };
+    (result, started.elapsed())
+}
+
+/// Write and flush without holding the PTY map lock. A write slower than
+/// WRITE_BLOCKED_THRESHOLD (the kernel buffer was full) emits
+/// `pty-write-blocked-{id}` once it completes.
+fn write_with_backpressure(
+    app: &AppHandle,
+    id: &str,
+    writer: &PtyWriter,
+    data: &[u8],
+) -> std::io::Result<()> {
+    let (result, elapsed) = timed_write(writer, data);
+    if elapsed >= WRITE_BLOCKED_THRESHOLD {
+        let _ = app.emit(
+            &format!("pty-write-blocked-{}", id),
+            PtyWriteBlocked {
+                elapsed_ms: elapsed.as_millis() as u64,
+                bytes: data.len(),
+            },
+        );
+    }
+    result
+}
+
+#[tauri::command]
+pub fn write_pty(
+    id: String,
+    data: String,
+    app: AppHandle,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    if let Some(writer) = pty_writer(&state, &id) {
+        write_with_backpressure(&app, &id, &writer, data.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Write the same input to several PTYs (broadcast input groups). Returns
+/// the ids that couldn't be written to instead of stopping at the first.
+#[tauri::command]
+pub fn write_pty_broadcast(
+    ids: Vec<String>,
+    data: String,
+    app: AppHandle,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<Vec<String>, String> {
+    let failed = ids
+        .into_iter()
+        .filter(|id| {
+            let written = pty_writer(&state, id).is_some_and(|writer| {
+                write_with_backpressure(&app, id, &writer, data.as_bytes()).is_ok()
+            });
+            !written
+        })
+        .collect();
+    Ok(failed)
+}
+
+/// Run a user-entered script in a new pane. The script goes to a temp file
+/// that the shell runs and then deletes, so quotes and newlines reach it
+/// untouched instead of being interpolated into a `-c` string.
+#[tauri::command]
+pub fn spawn_pty_with_script(
+    id: String,
+    cwd: String,
+    script: String,
+    cols: u16,
+    rows: u16,
+    app: AppHandle,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let safe_id: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let script_path = std::env::temp_dir().join(format!("aterm-script-{}-{}.sh", safe_id, millis));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&script_path).map_err(|e| e.to_string())?;
+    file.write_all(script.as_bytes())
+        .map_err(|e| e.to_string())?;
+    drop(file);
+
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+    let quoted_path = shell_quote(&script_path.to_string_lossy());
+    let command = format!(
+        "{} {}; rm -f {}",
+        shell_quote(&shell),
+        quoted_path,
+        quoted_path
+    );
+
+    let result = spawn_pty(
+        id,
+        cwd,
+        cols,
+        rows,
+        Some(command),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        app,
+        state,
+    );
+    if result.is_err() {
+        let _ = std::fs::remove_file(&script_path);
     }
+    result
+}
+
+/// Quote a string for safe interpolation into a POSIX shell command line
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Change the shell's working directory by typing a `cd` command into the PTY.
+/// The leading space keeps it out of history when HIST_IGNORE_SPACE is set.
+#[tauri::command]
+pub fn pty_cd(
+    id: String,
+    path: String,
+    app: AppHandle,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    if !std::path::Path::new(&path).is_dir() {
+        return Err(format!("Directory does not exist: {}", path));
+    }
+
+    let writer = pty_writer(&state, &id).ok_or_else(|| format!("PTY not found: {}", id))?;
+    let command = format!(" cd {}\n", shell_quote(&path));
+    write_with_backpressure(&app, &id, &writer, command.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Export a pane's retained output as a standalone HTML file with colors
+#[tauri::command]
+pub fn export_pty_buffer_html(
+    id: String,
+    output_path: String,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    let contents = {
+        let handle = get_pty(&state, &id)?;
+        let scrollback = handle.lock().unwrap().scrollback.clone();
+        let contents = scrollback.lock().unwrap().contents();
+        contents
+    };
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>aterm output</title>\n\
+         <style>body {{ background: #1e1e1e; color: #d4d4d4; }} \
+         pre {{ font-family: Menlo, Monaco, monospace; font-size: 13px; }}</style>\n\
+         </head>\n<body>\n<pre>{}</pre>\n</body>\n</html>\n",
+        ansi_to_html(&contents)
+    );
+
+    std::fs::write(&output_path, html).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_pty_scrollback_limit(
+    id: String,
+    bytes: usize,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    let handle = get_pty(&state, &id)?;
+    let pty = handle.lock().unwrap();
+    pty.scrollback.lock().unwrap().set_limit(bytes);
+    Ok(())
+}
+
+/// A pane's retained output as text (escape sequences included)
+#[tauri::command]
+pub fn get_pty_scrollback(id: String, state: tauri::State<'_, PtyMap>) -> Result<String, String> {
+    let handle = get_pty(&state, &id)?;
+    let pty = handle.lock().unwrap();
+    let contents = pty.scrollback.lock().unwrap().contents();
+    Ok(String::from_utf8_lossy(&contents).to_string())
+}
+
+/// Shell integration (OSC 133) markers seen in a pane, oldest first
+#[tauri::command]
+pub fn get_command_marks(
+    id: String,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<Vec<PromptMark>, String> {
+    let handle = get_pty(&state, &id)?;
+    let pty = handle.lock().unwrap();
+    let marks = pty.command_marks.lock().unwrap().clone();
+    Ok(marks)
+}
+
+/// Output of the most recently finished command: everything between its
+/// OSC 133 "C" (output start) and "D" (finished) markers
+#[tauri::command]
+pub fn get_last_command_output(
+    id: String,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<CommandOutput, String> {
+    let handle = get_pty(&state, &id)?;
+    let pty = handle.lock().unwrap();
+
+    let (start, end, exit_code) = {
+        let marks = pty.command_marks.lock().unwrap();
+        let finished = marks.iter().rposition(|m| m.kind == "D");
+        let started = finished.and_then(|d| marks[..d].iter().rposition(|m| m.kind == "C"));
+        match (started, finished) {
+            (Some(c), Some(d)) => (marks[c].offset, marks[d].offset, marks[d].exit_code),
+            _ => {
+                return Err(
+                    "No command marks available (shell integration not installed?)".to_string(),
+                )
+            }
+        }
+    };
+
+    let bytes = pty
+        .scrollback
+        .lock()
+        .unwrap()
+        .range(start, end)
+        .ok_or_else(|| "Command output is no longer retained".to_string())?;
+
+    Ok(CommandOutput {
+        start,
+        end,
+        text: strip_ansi(&bytes),
+        exit_code,
+    })
+}
+
+/// Bytes of output currently retained for a pane
+#[tauri::command]
+pub fn get_pty_scrollback_usage(
+    id: String,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<usize, String> {
+    let handle = get_pty(&state, &id)?;
+    let pty = handle.lock().unwrap();
+    let usage = pty.scrollback.lock().unwrap().retained_bytes();
+    Ok(usage)
+}
+
+/// Set (or clear, with an empty title) the user-defined name of a pane. The
+/// title is saved, and a pane respawned under the same id gets it back.
+#[tauri::command]
+pub fn set_pty_title(
+    id: String,
+    title: Option<String>,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let handle = get_pty(&state, &id)?;
+    handle.lock().unwrap().title = title.clone();
+    save_pane_title(&id, title.as_deref())
+}
+
+#[tauri::command]
+pub fn get_pty_info(id: String, state: tauri::State<'_, PtyMap>) -> Result<PtyInfo, String> {
+    let handle = get_pty(&state, &id)?;
+    let pty = handle.lock().unwrap();
+    let last_seq = pty.emitted.lock().unwrap().delivered();
+    Ok(PtyInfo {
+        title: pty.title.clone(),
+        pid: pty.child.process_id(),
+        last_seq,
+        id,
+    })
+}
+
+/// Replay a pane's retained output as one `pty-replay-{id}` event, e.g.
+/// after the webview reloads and resubscribes. It covers everything
+/// retained, including output still queued for emission; live
+/// `pty-output-{id}` events then pick up at its `end`, so nothing is
+/// duplicated.
+#[tauri::command]
+pub fn reattach_pty(
+    id: String,
+    app: AppHandle,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    let (scrollback, emitted) = {
+        let handle = get_pty(&state, &id)?;
+        let pty = handle.lock().unwrap();
+        (pty.scrollback.clone(), pty.emitted.clone())
+    };
+
+    // Holding this blocks live emission until the replay is out
+    let mut cursor = emitted.lock().unwrap();
+    let (start, end, data) = {
+        let scrollback = scrollback.lock().unwrap();
+        let start = scrollback.start_offset();
+        if start <= cursor.emitted {
+            (start, scrollback.end_offset(), scrollback.contents())
+        } else {
+            // Queued output has pushed everything already emitted out of the
+            // scrollback. The queue still delivers it in order, so there's
+            // nothing to replay ahead of it.
+            (cursor.emitted, cursor.emitted, Vec::new())
+        }
+    };
+    cursor.replayed = cursor.replayed.max(end);
+    let _ = app.emit(
+        &format!("pty-replay-{}", id),
+        PtyReplay {
+            start,
+            end,
+            data: BASE64.encode(&data),
+        },
+    );
     Ok(())
 }
 
@@ -113,9 +787,11 @@ pub fn resize_pty(
     rows: u16,
     state: tauri::State<'_, PtyMap>,
 ) -> Result<(), String> {
-    let ptys = state.lock().unwrap();
-    if let Some(pty) = ptys.get(&id) {
-        pty.master
+    if let Ok(handle) = get_pty(&state, &id) {
+        handle
+            .lock()
+            .unwrap()
+            .master
             .resize(PtySize {
                 rows,
                 cols,
@@ -127,26 +803,91 @@ pub fn resize_pty(
     Ok(())
 }
 
+/// Resize several PTYs in one call (e.g. after a layout change). Returns
+/// the ids that couldn't be resized.
+#[tauri::command]
+pub fn resize_ptys(resizes: Vec<PtyResize>, state: tauri::State<'_, PtyMap>) -> Vec<String> {
+    resizes
+        .into_iter()
+        .filter(|resize| {
+            let resized = get_pty(&state, &resize.id).is_ok_and(|handle| {
+                let pty = handle.lock().unwrap();
+                pty.master
+                    .resize(PtySize {
+                        rows: resize.rows,
+                        cols: resize.cols,
+                        pixel_width: 0,
+                        pixel_height: 0,
+                    })
+                    .is_ok()
+            });
+            !resized
+        })
+        .map(|resize| resize.id)
+        .collect()
+}
+
+/// Send a signal to the process group in the PTY's foreground (the running
+/// job, or the shell itself when idle), leaving the pane in place
+#[tauri::command]
+pub fn signal_pty(
+    id: String,
+    signal: String,
+    state: tauri::State<'_, PtyMap>,
+) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        let signum = match signal.to_ascii_uppercase().as_str() {
+            "SIGINT" | "INT" => libc::SIGINT,
+            "SIGTERM" | "TERM" => libc::SIGTERM,
+            "SIGKILL" | "KILL" => libc::SIGKILL,
+            "SIGHUP" | "HUP" => libc::SIGHUP,
+            _ => return Err(format!("Unsupported signal: {}", signal)),
+        };
+
+        let handle = get_pty(&state, &id)?;
+        let pty = handle.lock().unwrap();
+        // The shell leads its own process group, so it's the fallback target
+        let pgid = pty
+            .master
+            .process_group_leader()
+            .or_else(|| pty.child.process_id().map(|pid| pid as libc::pid_t))
+            .ok_or_else(|| format!("No process to signal in PTY: {}", id))?;
+
+        if unsafe { libc::killpg(pgid, signum) } != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (id, state);
+        Err(format!(
+            "Sending {} is not supported on this platform",
+            signal
+        ))
+    }
+}
+
 #[tauri::command]
 pub fn kill_pty(id: String, state: tauri::State<'_, PtyMap>) -> Result<(), String> {
-    let mut ptys = state.lock().unwrap();
-    if let Some(mut pty) = ptys.remove(&id) {
-        let _ = pty.child.kill();
+    let handle = state.write().unwrap().remove(&id);
+    if let Some(handle) = handle {
+        let _ = handle.lock().unwrap().child.kill();
     }
     Ok(())
 }
 
 #[tauri::command]
 pub fn get_active_pty_count(state: tauri::State<'_, PtyMap>) -> usize {
-    let ptys = state.lock().unwrap();
-    ptys.len()
+    state.read().unwrap().len()
 }
 
 #[tauri::command]
 pub fn kill_all_ptys(state: tauri::State<'_, PtyMap>) -> Result<(), String> {
-    let mut ptys = state.lock().unwrap();
-    for (_, mut pty) in ptys.drain() {
-        let _ = pty.child.kill();
+    let handles: Vec<_> = state.write().unwrap().drain().collect();
+    for (_, handle) in handles {
+        let _ = handle.lock().unwrap().child.kill();
     }
     Ok(())
 }
@@ -154,12 +895,277 @@ pub fn kill_all_ptys(state: tauri::State<'_, PtyMap>) -> Result<(), String> {
 #[tauri::command]
 pub fn force_exit(state: tauri::State<'_, PtyMap>) {
     // Kill all PTYs first
-    {
-        let mut ptys = state.lock().unwrap();
-        for (_, mut pty) in ptys.drain() {
-            let _ = pty.child.kill();
-        }
+    let handles: Vec<_> = state.write().unwrap().drain().collect();
+    for (_, handle) in handles {
+        let _ = handle.lock().unwrap().child.kill();
     }
     // Exit the process
     std::process::exit(0);
 }
+
+/// Remove PTYs whose child process has already exited, emitting
+/// `pty-exit-{id}` for each. Returns the ids that were reaped.
+pub fn reap_exited_ptys(map: &PtyMap, app: &AppHandle) -> Vec<String> {
+    let handles: Vec<(String, Arc<Mutex<PtyHandle>>)> = map
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(id, handle)| (id.clone(), handle.clone()))
+        .collect();
+
+    let mut reaped = Vec::new();
+    for (id, handle) in handles {
+        let status = match handle.lock().unwrap().child.try_wait() {
+            Ok(Some(status)) => status,
+            _ => continue,
+        };
+
+        // Skip panes that were removed or respawned in the meantime
+        let removed = {
+            let mut ptys = map.write().unwrap();
+            match ptys.get(&id) {
+                Some(current) if Arc::ptr_eq(current, &handle) => ptys.remove(&id).is_some(),
+                _ => false,
+            }
+        };
+        if removed {
+            let _ = app.emit(&format!("pty-exit-{}", id), PtyExit::from(&status));
+            reaped.push(id);
+        }
+    }
+    reaped
+}
+
+/// Periodically reap exited PTYs so the map stays accurate even when the
+/// frontend misses a `kill_pty` call (e.g. a crashed window)
+pub fn spawn_pty_reaper(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(REAP_INTERVAL);
+        let map = app.state::<PtyMap>().inner().clone();
+        reap_exited_ptys(&map, &app);
+    });
+}
+
+#[tauri::command]
+pub fn reap_dead_ptys(app: AppHandle, state: tauri::State<'_, PtyMap>) -> Vec<String> {
+    reap_exited_ptys(state.inner(), &app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    // Stands in for a PTY whose kernel buffer fills up: accepts at most
+    // `chunk` bytes per call after `delay`, interrupts the first call, and
+    // accepts nothing once `capacity` bytes are buffered
+    struct ChokedWriter {
+        buffer: Arc<Mutex<Vec<u8>>>,
+        capacity: usize,
+        chunk: usize,
+        delay: Duration,
+        calls: Arc<Mutex<usize>>,
+    }
+
+    impl Write for ChokedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let calls = {
+                let mut calls = self.calls.lock().unwrap();
+                *calls += 1;
+                *calls
+            };
+            if calls == 1 {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            thread::sleep(self.delay);
+            let mut buffer = self.buffer.lock().unwrap();
+            let n = buf.len().min(self.chunk).min(self.capacity - buffer.len());
+            buffer.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn choked_writer(
+        capacity: usize,
+        chunk: usize,
+        delay: Duration,
+    ) -> (PtyWriter, Arc<Mutex<Vec<u8>>>, Arc<Mutex<usize>>) {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let calls = Arc::new(Mutex::new(0));
+        let writer: Box<dyn Write + Send> = Box::new(ChokedWriter {
+            buffer: buffer.clone(),
+            capacity,
+            chunk,
+            delay,
+            calls: calls.clone(),
+        });
+        (Arc::new(Mutex::new(writer)), buffer, calls)
+    }
+
+    #[test]
+    fn partial_and_interrupted_writes_are_retried() {
+        let (writer, buffer, calls) = choked_writer(1024, 4, Duration::ZERO);
+        let (result, _) = timed_write(&writer, b"echo hello world\n");
+        assert!(result.is_ok());
+        assert_eq!(buffer.lock().unwrap().as_slice(), b"echo hello world\n");
+        // One interrupted call, then 17 bytes in chunks of 4
+        assert_eq!(*calls.lock().unwrap(), 6);
+    }
+
+    #[test]
+    fn slow_write_is_reported_as_blocked() {
+        let delay = WRITE_BLOCKED_THRESHOLD / 2;
+        let (writer, buffer, _) = choked_writer(1024, 2, delay);
+        let (result, elapsed) = timed_write(&writer, b"abcdef");
+        assert!(result.is_ok());
+        assert_eq!(buffer.lock().unwrap().as_slice(), b"abcdef");
+        assert!(elapsed >= WRITE_BLOCKED_THRESHOLD);
+    }
+
+    #[test]
+    fn full_buffer_fails_without_losing_accepted_bytes() {
+        let (writer, buffer, _) = choked_writer(5, 3, Duration::ZERO);
+        let (result, elapsed) = timed_write(&writer, b"0123456789");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::WriteZero);
+        assert_eq!(buffer.lock().unwrap().as_slice(), b"01234");
+        assert!(elapsed < WRITE_BLOCKED_THRESHOLD);
+    }
+
+    #[test]
+    fn blocked_write_only_holds_its_own_writer() {
+        let (slow, _, _) = choked_writer(1024, 1, WRITE_BLOCKED_THRESHOLD);
+        let (fast, fast_buffer, _) = choked_writer(1024, 1024, Duration::ZERO);
+
+        let blocked = thread::spawn(move || timed_write(&slow, b"abc").0);
+        thread::sleep(Duration::from_millis(20));
+        let (result, elapsed) = timed_write(&fast, b"ls\n");
+        assert!(result.is_ok());
+        assert!(elapsed < WRITE_BLOCKED_THRESHOLD);
+        assert_eq!(fast_buffer.lock().unwrap().as_slice(), b"ls\n");
+        assert!(blocked.join().unwrap().is_ok());
+    }
+
+    // A PTY running `cat` into `output`, with its terminal output drained so
+    // echo can never back up
+    fn spawn_cat(output: &std::path::Path) -> PtyHandle {
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .unwrap();
+        let mut cmd = CommandBuilder::new("sh");
+        cmd.args([
+            "-c",
+            &format!(
+                "stty -echo; exec cat > {}",
+                shell_quote(&output.to_string_lossy())
+            ),
+        ]);
+        let child = pair.slave.spawn_command(cmd).unwrap();
+
+        let mut reader = pair.master.try_clone_reader().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while matches!(reader.read(&mut buf), Ok(n) if n > 0) {}
+        });
+
+        PtyHandle {
+            writer: Arc::new(Mutex::new(pair.master.take_writer().unwrap())),
+            master: pair.master,
+            child,
+            scrollback: Arc::new(Mutex::new(Scrollback::new(0))),
+            title: None,
+            command_marks: Arc::new(Mutex::new(Vec::new())),
+            emitted: Arc::new(Mutex::new(EmitCursor::default())),
+        }
+    }
+
+    #[test]
+    fn concurrent_writes_across_ptys() {
+        const PTYS: usize = 8;
+        const WRITERS: usize = 4;
+        const LINES: usize = 200;
+
+        let dir = std::env::temp_dir().join(format!("aterm-pty-stress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let map: PtyMap = Arc::new(RwLock::new(HashMap::new()));
+        for i in 0..PTYS {
+            let handle = spawn_cat(&dir.join(i.to_string()));
+            map.write()
+                .unwrap()
+                .insert(i.to_string(), Arc::new(Mutex::new(handle)));
+        }
+
+        // Every writer sends its lines to every PTY, looking the writer up
+        // through the map each time like write_pty does
+        let (done_tx, done_rx) = mpsc::channel();
+        for w in 0..WRITERS {
+            let map = map.clone();
+            let done_tx = done_tx.clone();
+            thread::spawn(move || {
+                for line in 0..LINES {
+                    for i in 0..PTYS {
+                        let writer = pty_writer(&map, &i.to_string()).unwrap();
+                        let data = format!("{}:{}\n", w, line);
+                        timed_write(&writer, data.as_bytes()).0.unwrap();
+                    }
+                }
+                let _ = done_tx.send(());
+            });
+        }
+
+        // Resizes take each PTY's own lock while the writes are in flight
+        let resizer = {
+            let map = map.clone();
+            thread::spawn(move || {
+                for round in 0..100u16 {
+                    for i in 0..PTYS {
+                        let handle = get_pty(&map, &i.to_string()).unwrap();
+                        let size = PtySize {
+                            rows: 24 + round % 10,
+                            cols: 80,
+                            pixel_width: 0,
+                            pixel_height: 0,
+                        };
+                        handle.lock().unwrap().master.resize(size).unwrap();
+                    }
+                }
+            })
+        };
+
+        for _ in 0..WRITERS {
+            done_rx
+                .recv_timeout(Duration::from_secs(30))
+                .expect("writers deadlocked");
+        }
+        resizer.join().unwrap();
+
+        // Remove the PTYs the way kill_pty does, then end each `cat` with EOF
+        let handles: Vec<_> = map.write().unwrap().drain().collect();
+        for (id, handle) in handles {
+            let writer = handle.lock().unwrap().writer.clone();
+            timed_write(&writer, b"\x04").0.unwrap();
+            assert!(handle.lock().unwrap().child.wait().unwrap().success());
+
+            let contents = std::fs::read_to_string(dir.join(&id)).unwrap();
+            let received: Vec<&str> = contents.lines().collect();
+            assert_eq!(received.len(), WRITERS * LINES, "lost writes on PTY {}", id);
+            for w in 0..WRITERS {
+                let prefix = format!("{}:", w);
+                let lines: Vec<usize> = received
+                    .iter()
+                    .filter_map(|l| l.strip_prefix(&prefix)?.parse().ok())
+                    .collect();
+                assert_eq!(lines, (0..LINES).collect::<Vec<_>>());
+            }
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
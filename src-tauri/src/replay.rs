@@ -0,0 +1,157 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub type ReplayMap = Arc<Mutex<HashMap<String, Arc<ReplayControl>>>>;
+
+#[derive(Default)]
+pub struct ReplayControl {
+    paused: AtomicBool,
+    stopped: AtomicBool,
+}
+
+// Granularity for honoring pause/stop while waiting between frames
+const TICK: Duration = Duration::from_millis(20);
+// Upper bound on a single gap, so a huge timestamp or tiny speed can't panic
+const MAX_FRAME_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Parse the output frames ("o" events) of an asciinema v2 recording into
+/// (timestamp, data) pairs
+fn read_cast_frames(cast_path: &str) -> Result<Vec<(f64, String)>, String> {
+    let content = fs::read_to_string(cast_path).map_err(|e| e.to_string())?;
+    let mut lines = content.lines();
+
+    let header: Value = lines
+        .next()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .ok_or_else(|| "Invalid cast file: missing header".to_string())?;
+    if header.get("version").and_then(|v| v.as_u64()) != Some(2) {
+        return Err("Unsupported cast version (expected asciinema v2)".to_string());
+    }
+
+    let frames = lines
+        .filter_map(|line| {
+            let event: Value = serde_json::from_str(line).ok()?;
+            let event = event.as_array()?;
+            if event.get(1)?.as_str()? != "o" {
+                return None;
+            }
+            Some((
+                event.first()?.as_f64()?,
+                event.get(2)?.as_str()?.to_string(),
+            ))
+        })
+        .collect();
+
+    Ok(frames)
+}
+
+/// Wait for `duration`, stretched by any time spent paused. Returns false if
+/// the replay was stopped meanwhile.
+fn wait_frame(control: &ReplayControl, duration: Duration) -> bool {
+    let mut remaining = duration;
+    loop {
+        if control.stopped.load(Ordering::Relaxed) {
+            return false;
+        }
+        if control.paused.load(Ordering::Relaxed) {
+            thread::sleep(TICK);
+            continue;
+        }
+        if remaining.is_zero() {
+            return true;
+        }
+        let step = remaining.min(TICK);
+        thread::sleep(step);
+        remaining -= step;
+    }
+}
+
+/// Play an asciinema recording into a pane by emitting its output on the
+/// regular `pty-output-{id}` channel, with the recorded timing scaled by
+/// `speed`. Emits `pty-replay-done-{id}` when playback ends.
+#[tauri::command]
+pub fn replay_cast(
+    id: String,
+    cast_path: String,
+    speed: Option<f64>,
+    app: AppHandle,
+    state: tauri::State<'_, ReplayMap>,
+) -> Result<(), String> {
+    let speed = speed.filter(|s| *s > 0.0).unwrap_or(1.0);
+    let frames = read_cast_frames(&cast_path)?;
+
+    let control = Arc::new(ReplayControl::default());
+    {
+        let mut replays = state.lock().unwrap();
+        if let Some(previous) = replays.insert(id.clone(), control.clone()) {
+            previous.stopped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let replays = state.inner().clone();
+    thread::spawn(move || {
+        let mut last_time = 0.0;
+        for (time, data) in frames {
+            let delay = ((time - last_time).max(0.0)) / speed;
+            last_time = time;
+            let delay = Duration::try_from_secs_f64(delay)
+                .unwrap_or(MAX_FRAME_DELAY)
+                .min(MAX_FRAME_DELAY);
+            if !wait_frame(&control, delay) {
+                return;
+            }
+            let encoded = BASE64.encode(data.as_bytes());
+            let _ = app.emit(&format!("pty-output-{}", id), encoded);
+        }
+
+        let mut replays = replays.lock().unwrap();
+        if replays
+            .get(&id)
+            .is_some_and(|current| Arc::ptr_eq(current, &control))
+        {
+            replays.remove(&id);
+        }
+        let _ = app.emit(&format!("pty-replay-done-{}", id), ());
+    });
+
+    Ok(())
+}
+
+fn with_replay(
+    id: &str,
+    state: &tauri::State<'_, ReplayMap>,
+    f: impl FnOnce(&ReplayControl),
+) -> Result<(), String> {
+    let replays = state.lock().unwrap();
+    let control = replays
+        .get(id)
+        .ok_or_else(|| format!("No replay running for {}", id))?;
+    f(control);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn pause_replay(id: String, state: tauri::State<'_, ReplayMap>) -> Result<(), String> {
+    with_replay(&id, &state, |c| c.paused.store(true, Ordering::Relaxed))
+}
+
+#[tauri::command]
+pub fn resume_replay(id: String, state: tauri::State<'_, ReplayMap>) -> Result<(), String> {
+    with_replay(&id, &state, |c| c.paused.store(false, Ordering::Relaxed))
+}
+
+#[tauri::command]
+pub fn stop_replay(id: String, state: tauri::State<'_, ReplayMap>) -> Result<(), String> {
+    let mut replays = state.lock().unwrap();
+    if let Some(control) = replays.remove(&id) {
+        control.stopped.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
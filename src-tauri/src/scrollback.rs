@@ -0,0 +1,85 @@
+use crate::config::load_config;
+use std::collections::VecDeque;
+
+// Bytes of PTY output retained per pane unless configured otherwise
+pub const DEFAULT_SCROLLBACK_BYTES: usize = 256 * 1024;
+
+/// Retention limit for new panes, from the `ptyScrollbackBytes` config key
+pub fn default_scrollback_limit() -> usize {
+    load_config()
+        .ok()
+        .and_then(|config| config.get("ptyScrollbackBytes")?.as_u64())
+        .map(|bytes| bytes as usize)
+        .unwrap_or(DEFAULT_SCROLLBACK_BYTES)
+}
+
+/// Bounded buffer of the most recent raw PTY output. When full, the oldest
+/// bytes are dropped from the front.
+pub struct Scrollback {
+    data: VecDeque<u8>,
+    limit: usize,
+    pushed: usize, // Total bytes ever pushed, retained or not
+}
+
+impl Scrollback {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            limit,
+            pushed: 0,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.pushed += bytes.len();
+        if self.limit == 0 {
+            return;
+        }
+        // Only the tail of an oversized chunk can survive trimming anyway
+        let start = bytes.len().saturating_sub(self.limit);
+        self.data.extend(&bytes[start..]);
+        self.trim();
+    }
+
+    /// Change the retention limit, trimming existing output from the front
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    pub fn retained_bytes(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn contents(&self) -> Vec<u8> {
+        self.data.iter().copied().collect()
+    }
+
+    /// Output stream offset of the oldest retained byte
+    pub fn start_offset(&self) -> usize {
+        self.pushed - self.data.len()
+    }
+
+    /// Output stream offset just past the newest byte
+    pub fn end_offset(&self) -> usize {
+        self.pushed
+    }
+
+    /// Bytes between two output stream offsets (as counted from the first
+    /// push), or None if part of the range has already been dropped
+    pub fn range(&self, start: usize, end: usize) -> Option<Vec<u8>> {
+        let first_retained = self.start_offset();
+        if start < first_retained || end > self.pushed || start > end {
+            return None;
+        }
+        let (start, end) = (start - first_retained, end - first_retained);
+        Some(self.data.range(start..end).copied().collect())
+    }
+
+    fn trim(&mut self) {
+        if self.data.len() > self.limit {
+            let excess = self.data.len() - self.limit;
+            self.data.drain(..excess);
+        }
+    }
+}
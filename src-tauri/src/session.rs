@@ -0,0 +1,181 @@
+use crate::config::{load_state_file, save_state_file};
+use crate::worktree::validate_ssh_target_part;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::thread;
+
+// File listing tmux-backed panes, so they can be found again after a crash
+// takes the app (and its PTYs) down
+const PANE_SESSIONS_FILE: &str = "pane-sessions.json";
+// File mapping PTY ids to user-assigned pane titles
+const PANE_TITLES_FILE: &str = "pane-titles.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaneSession {
+    pane_id: String,
+    window_label: String,
+    tmux_session: String,
+    host: Option<String>, // ssh host for remote tmux; None for local
+    #[serde(default)]
+    title: Option<String>, // User-assigned pane title, to re-apply on reattach
+}
+
+fn load_pane_sessions() -> Vec<PaneSession> {
+    load_state_file(PANE_SESSIONS_FILE).unwrap_or_default()
+}
+
+fn save_pane_sessions(sessions: &[PaneSession]) -> Result<(), String> {
+    save_state_file(PANE_SESSIONS_FILE, &sessions)
+}
+
+fn load_pane_titles() -> HashMap<String, String> {
+    load_state_file(PANE_TITLES_FILE).unwrap_or_default()
+}
+
+/// The title the user gave a pane in an earlier session, if any
+pub fn saved_pane_title(pane_id: &str) -> Option<String> {
+    load_pane_titles().remove(pane_id)
+}
+
+/// Remember (or, with None, forget) a pane's user-assigned title so it
+/// survives a restart
+pub fn save_pane_title(pane_id: &str, title: Option<&str>) -> Result<(), String> {
+    let mut titles = load_pane_titles();
+    let changed = match title {
+        Some(title) => {
+            titles
+                .insert(pane_id.to_string(), title.to_string())
+                .as_deref()
+                != Some(title)
+        }
+        None => titles.remove(pane_id).is_some(),
+    };
+    if !changed {
+        return Ok(());
+    }
+    save_state_file(PANE_TITLES_FILE, &titles)
+}
+
+/// Remember that a pane in the calling window is backed by a tmux session.
+/// Recording the same pane again (e.g. after it moves windows) replaces it.
+#[tauri::command]
+pub fn record_pane_session(
+    pane_id: String,
+    tmux_session: String,
+    host: Option<String>,
+    window: tauri::WebviewWindow,
+) -> Result<(), String> {
+    let host = host.filter(|h| !h.trim().is_empty());
+    if let Some(host) = &host {
+        validate_ssh_target_part("host", host)?;
+    }
+    let mut sessions = load_pane_sessions();
+    sessions.retain(|s| s.pane_id != pane_id);
+    sessions.push(PaneSession {
+        pane_id,
+        window_label: window.label().to_string(),
+        tmux_session,
+        host,
+        title: None,
+    });
+    save_pane_sessions(&sessions)
+}
+
+/// Stop tracking a pane, e.g. when the user closes it on purpose. Its saved
+/// title is forgotten too.
+#[tauri::command]
+pub fn forget_pane_session(pane_id: String) -> Result<(), String> {
+    save_pane_title(&pane_id, None)?;
+    let mut sessions = load_pane_sessions();
+    let before = sessions.len();
+    sessions.retain(|s| s.pane_id != pane_id);
+    if sessions.len() == before {
+        return Ok(());
+    }
+    save_pane_sessions(&sessions)
+}
+
+/// Names of the tmux sessions alive on a host (local when None), or None
+/// when the host couldn't be reached
+fn live_tmux_sessions(host: Option<&str>) -> Option<HashSet<String>> {
+    let list = ["tmux", "list-sessions", "-F", "#{session_name}"];
+    let output = match host {
+        Some(host) => Command::new("ssh")
+            .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5", "--", host])
+            .args(list)
+            .output()
+            .ok()?,
+        None => match Command::new(list[0]).args(&list[1..]).output() {
+            Ok(output) => output,
+            // No tmux installed means no sessions survived
+            Err(_) => return Some(HashSet::new()),
+        },
+    };
+
+    // ssh exits with 255 for its own failures; tmux exits non-zero when no
+    // server is running, which just means there are no sessions
+    if host.is_some() && output.status.code() == Some(255) {
+        return None;
+    }
+    if !output.status.success() {
+        return Some(HashSet::new());
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|name| name.to_string())
+            .collect(),
+    )
+}
+
+/// Persisted pane sessions whose tmux session is still alive, for the UI to
+/// offer reattaching after a crash. Records whose session is gone are
+/// pruned; those on unreachable hosts are kept but not returned.
+#[tauri::command]
+pub async fn recover_sessions() -> Result<Vec<PaneSession>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let sessions = load_pane_sessions();
+        let hosts: HashSet<Option<String>> = sessions.iter().map(|s| s.host.clone()).collect();
+
+        // Check each host once, concurrently, since remotes can be slow
+        let live: HashMap<Option<String>, Option<HashSet<String>>> = thread::scope(|scope| {
+            let checks: Vec<_> = hosts
+                .into_iter()
+                .map(|host| {
+                    scope.spawn(move || {
+                        let live = live_tmux_sessions(host.as_deref());
+                        (host, live)
+                    })
+                })
+                .collect();
+            checks.into_iter().filter_map(|c| c.join().ok()).collect()
+        });
+
+        let titles = load_pane_titles();
+        let mut alive = Vec::new();
+        let mut kept = Vec::new();
+        let mut pruned = false;
+        for session in sessions {
+            match live.get(&session.host) {
+                Some(Some(names)) if names.contains(&session.tmux_session) => {
+                    alive.push(PaneSession {
+                        title: titles.get(&session.pane_id).cloned(),
+                        ..session.clone()
+                    });
+                    kept.push(session);
+                }
+                Some(Some(_)) => pruned = true,
+                _ => kept.push(session),
+            }
+        }
+
+        if pruned {
+            save_pane_sessions(&kept)?;
+        }
+        Ok(alive)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,338 @@
+use crate::directory::expand_path;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Settings pulled from another terminal's config, normalized so the
+// frontend can pre-fill a profile from either source
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalImport {
+    source: String, // Path of the config file that was read
+    shell_program: Option<String>,
+    shell_args: Vec<String>,
+    working_directory: Option<String>,
+    font_family: Option<String>,
+    font_size: Option<f64>,
+    color_scheme: Option<String>, // Named scheme (WezTerm)
+    colors: Option<ImportedColors>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedColors {
+    background: Option<String>,
+    foreground: Option<String>,
+    normal: Vec<String>, // black, red, green, yellow, blue, magenta, cyan, white
+    bright: Vec<String>,
+}
+
+const ANSI_COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+// Guards against import cycles between Alacritty config files
+const MAX_IMPORT_DEPTH: usize = 8;
+
+fn xdg_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+}
+
+fn first_existing(candidates: Vec<PathBuf>) -> Option<PathBuf> {
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// Overlay `overrides` onto `base`, merging nested tables key by key
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) => {
+                merge_tables(existing, nested)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Convert a value from a legacy YAML config into its TOML equivalent.
+/// Nulls (keys left empty) are dropped since TOML has no null.
+fn yaml_to_toml(value: serde_yaml::Value) -> Option<toml::Value> {
+    use serde_yaml::Value as Yaml;
+    Some(match value {
+        Yaml::Null => return None,
+        Yaml::Bool(b) => toml::Value::Boolean(b),
+        Yaml::Number(n) => match n.as_i64() {
+            Some(i) => toml::Value::Integer(i),
+            None => toml::Value::Float(n.as_f64()?),
+        },
+        Yaml::String(s) => toml::Value::String(s),
+        Yaml::Sequence(items) => {
+            toml::Value::Array(items.into_iter().filter_map(yaml_to_toml).collect())
+        }
+        Yaml::Mapping(map) => toml::Value::Table(
+            map.into_iter()
+                .filter_map(|(key, value)| Some((key.as_str()?.to_string(), yaml_to_toml(value)?)))
+                .collect(),
+        ),
+        Yaml::Tagged(tagged) => return yaml_to_toml(tagged.value),
+    })
+}
+
+/// Parse an Alacritty config, TOML or legacy (pre-0.13) YAML. The YAML
+/// layout used the same keys, so both end up as the same table.
+fn parse_alacritty_file(path: &Path) -> Result<toml::Table, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let parse_error = |e: String| format!("Failed to parse {}: {}", path.display(), e);
+
+    let is_yaml = path
+        .extension()
+        .is_some_and(|ext| ext == "yml" || ext == "yaml");
+    if !is_yaml {
+        return content
+            .parse()
+            .map_err(|e: toml::de::Error| parse_error(e.to_string()));
+    }
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_str(&content).map_err(|e| parse_error(e.to_string()))?;
+    match yaml_to_toml(yaml) {
+        Some(toml::Value::Table(table)) => Ok(table),
+        // An empty file
+        None => Ok(toml::Table::new()),
+        Some(_) => Err(parse_error(
+            "expected a mapping at the top level".to_string(),
+        )),
+    }
+}
+
+/// Parse an Alacritty config along with the files it imports. Imports are
+/// applied first so the importing file's own settings win.
+fn load_alacritty_table(path: &Path, depth: usize) -> Result<toml::Table, String> {
+    let table = parse_alacritty_file(path)?;
+
+    // `import` moved under [general] in Alacritty 0.14
+    let imports: Vec<String> = table
+        .get("general")
+        .and_then(|general| general.get("import"))
+        .or_else(|| table.get("import"))
+        .and_then(|v| v.as_array())
+        .map(|paths| {
+            paths
+                .iter()
+                .filter_map(|p| p.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut merged = toml::Table::new();
+    if depth < MAX_IMPORT_DEPTH {
+        for import in imports {
+            let import_path = PathBuf::from(expand_path(&import));
+            // Missing imports are skipped, as Alacritty itself does
+            if let Ok(imported) = load_alacritty_table(&import_path, depth + 1) {
+                merge_tables(&mut merged, imported);
+            }
+        }
+    }
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+fn toml_str(table: &toml::Table, path: &[&str]) -> Option<String> {
+    let (last, parents) = path.split_last()?;
+    let mut current = table;
+    for key in parents {
+        current = current.get(*key)?.as_table()?;
+    }
+    current.get(*last)?.as_str().map(String::from)
+}
+
+fn alacritty_colors(table: &toml::Table) -> Option<ImportedColors> {
+    let colors = table.get("colors")?.as_table()?;
+    let palette = |section: &str| -> Vec<String> {
+        ANSI_COLOR_NAMES
+            .iter()
+            .map_while(|name| toml_str(colors, &[section, name]))
+            .collect()
+    };
+
+    Some(ImportedColors {
+        background: toml_str(colors, &["primary", "background"]),
+        foreground: toml_str(colors, &["primary", "foreground"]),
+        normal: palette("normal"),
+        bright: palette("bright"),
+    })
+}
+
+/// Read the Alacritty config (TOML, or the legacy YAML if there's no TOML
+/// one, following `import`s) into the normalized import shape
+#[tauri::command]
+pub fn get_alacritty_config() -> Result<TerminalImport, String> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let config_dir = xdg_config_dir().map(|dir| dir.join("alacritty"));
+
+    // Pre-0.13 configs were YAML; a TOML one takes precedence, as in Alacritty
+    let candidates = ["alacritty.toml", "alacritty.yml"]
+        .iter()
+        .flat_map(|name| {
+            config_dir
+                .iter()
+                .map(move |dir| dir.join(name))
+                .chain([home.join(format!(".{}", name))])
+        })
+        .collect();
+    let path =
+        first_existing(candidates).ok_or_else(|| "Alacritty config not found".to_string())?;
+
+    let table = load_alacritty_table(&path, 0)?;
+
+    // [shell] and top-level working_directory moved under [terminal] and
+    // [general] in Alacritty 0.14; accept either layout
+    let shell = table
+        .get("terminal")
+        .and_then(|terminal| terminal.get("shell"))
+        .or_else(|| table.get("shell"));
+    let (shell_program, shell_args) = match shell {
+        Some(toml::Value::String(program)) => (Some(program.clone()), Vec::new()),
+        Some(toml::Value::Table(shell)) => (
+            shell
+                .get("program")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            shell
+                .get("args")
+                .and_then(|v| v.as_array())
+                .map(|args| {
+                    args.iter()
+                        .filter_map(|a| a.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        _ => (None, Vec::new()),
+    };
+
+    let font_size = table
+        .get("font")
+        .and_then(|font| font.get("size"))
+        .and_then(|size| {
+            size.as_float()
+                .or_else(|| size.as_integer().map(|i| i as f64))
+        });
+
+    Ok(TerminalImport {
+        source: path.to_string_lossy().to_string(),
+        shell_program,
+        shell_args,
+        working_directory: toml_str(&table, &["general", "working_directory"])
+            .or_else(|| toml_str(&table, &["working_directory"])),
+        font_family: toml_str(&table, &["font", "normal", "family"]),
+        font_size,
+        color_scheme: None,
+        colors: alacritty_colors(&table),
+    })
+}
+
+/// Value of a simple `config.<key> = <value>` (or `<key> = <value>` inside a
+/// returned table) assignment in a WezTerm Lua config
+fn lua_assignment<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content.lines().find_map(|line| {
+        let line = line.trim();
+        if line.starts_with("--") {
+            return None;
+        }
+        let rest = line
+            .strip_prefix("config.")
+            .unwrap_or(line)
+            .strip_prefix(key)?
+            .trim_start()
+            .strip_prefix('=')?;
+        Some(rest.trim().trim_end_matches(',').trim())
+    })
+}
+
+/// Every quoted string in a Lua expression, in order
+fn lua_strings(value: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '"' || c == '\'' {
+            let s: String = chars.by_ref().take_while(|&ch| ch != c).collect();
+            strings.push(s);
+        }
+    }
+    strings
+}
+
+/// Best-effort read of the WezTerm config. The config is a Lua program, so
+/// only plain literal assignments are picked up; anything computed is
+/// left empty.
+#[tauri::command]
+pub fn get_wezterm_config() -> Result<TerminalImport, String> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let mut candidates = Vec::new();
+    if let Some(file) = std::env::var_os("WEZTERM_CONFIG_FILE") {
+        candidates.push(PathBuf::from(file));
+    }
+    if let Some(dir) = xdg_config_dir() {
+        candidates.push(dir.join("wezterm").join("wezterm.lua"));
+    }
+    candidates.push(home.join(".wezterm.lua"));
+
+    let path = first_existing(candidates).ok_or("WezTerm config not found")?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut prog = lua_assignment(&content, "default_prog")
+        .map(lua_strings)
+        .unwrap_or_default()
+        .into_iter();
+
+    Ok(TerminalImport {
+        source: path.to_string_lossy().to_string(),
+        shell_program: prog.next(),
+        shell_args: prog.collect(),
+        working_directory: lua_assignment(&content, "default_cwd")
+            .and_then(|v| lua_strings(v).into_iter().next()),
+        // Handles both wezterm.font("X") and wezterm.font_with_fallback({"X", ...})
+        font_family: lua_assignment(&content, "font")
+            .and_then(|v| lua_strings(v).into_iter().next()),
+        font_size: lua_assignment(&content, "font_size").and_then(|v| v.parse().ok()),
+        color_scheme: lua_assignment(&content, "color_scheme")
+            .and_then(|v| lua_strings(v).into_iter().next()),
+        colors: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_yaml_parses_like_toml() {
+        let dir = std::env::temp_dir().join(format!("aterm-alacritty-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let yaml = dir.join("alacritty.yml");
+        fs::write(
+            &yaml,
+            "shell:\n  program: /bin/zsh\n  args:\n    - -l\nworking_directory:\nfont:\n  size: 13\n  normal:\n    family: Menlo\ncolors:\n  primary:\n    background: '#1d1f21'\n",
+        )
+        .unwrap();
+        let toml = dir.join("alacritty.toml");
+        fs::write(
+            &toml,
+            "[shell]\nprogram = \"/bin/zsh\"\nargs = [\"-l\"]\n\n[font]\nsize = 13\nnormal = { family = \"Menlo\" }\n\n[colors.primary]\nbackground = \"#1d1f21\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_alacritty_file(&yaml).unwrap(),
+            parse_alacritty_file(&toml).unwrap()
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::directory::ignored_dirs;
+use crate::git_timing::TimedCommand;
+use notify::event::{EventKind, ModifyKind};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+pub type GitWatcherMap = Arc<Mutex<HashMap<String, RecommendedWatcher>>>;
+
+// Project tree watchers by watch id
+pub type ProjectWatcherMap = Arc<Mutex<HashMap<u64, RecommendedWatcher>>>;
+
+static NEXT_WATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFileChange {
+    path: String, // Relative to the watched root
+    kind: String, // "created", "modified", or "removed"
+}
+
+// Payload of `project-file-changed`: every change from one burst
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFileChanges {
+    watch_id: String,
+    changes: Vec<ProjectFileChange>,
+}
+
+// Quiet period before a burst of filesystem events is reported
+const DEBOUNCE: Duration = Duration::from_millis(300);
+// Longest a continuous stream of events (e.g. a build writing into the
+// tree) can hold back a project report
+const MAX_DEBOUNCE: Duration = Duration::from_secs(2);
+
+fn absolute_git_dir(path: &str) -> Result<PathBuf, String> {
+    let output = std::process::Command::new("git")
+        .args(["-C", path, "rev-parse", "--absolute-git-dir"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err("Not a git repository".to_string());
+    }
+
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Whether a changed path can affect `git status`: the index, HEAD, refs, or
+/// any working tree file outside the ignored directories
+fn is_status_relevant(
+    changed: &Path,
+    root: &Path,
+    git_dir: &Path,
+    ignored_dirs: &HashSet<String>,
+) -> bool {
+    if let Ok(rel) = changed.strip_prefix(git_dir) {
+        let rel = rel.to_string_lossy();
+        return rel == "index" || rel == "HEAD" || rel.starts_with("refs");
+    }
+
+    match changed.strip_prefix(root) {
+        Ok(rel) => !rel
+            .components()
+            .any(|c| ignored_dirs.contains(c.as_os_str().to_string_lossy().as_ref())),
+        Err(_) => false,
+    }
+}
+
+/// Watch a repository and emit a debounced `git-status-changed` event (with
+/// the repo path as payload) whenever something affecting its status changes
+#[tauri::command]
+pub fn watch_git_status(
+    path: String,
+    app: AppHandle,
+    state: tauri::State<'_, GitWatcherMap>,
+) -> Result<(), String> {
+    let mut watchers = state.lock().unwrap();
+    if watchers.contains_key(&path) {
+        return Ok(());
+    }
+
+    // Events carry resolved paths, so resolve both to match them
+    let root = std::fs::canonicalize(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let git_dir = absolute_git_dir(&path)?;
+    let git_dir = std::fs::canonicalize(&git_dir).unwrap_or(git_dir);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+
+    // Linked worktrees keep their git dir outside the working tree
+    if !git_dir.starts_with(&root) {
+        watcher
+            .watch(&git_dir, RecursiveMode::Recursive)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let event_path = path.clone();
+    let ignored_dirs = ignored_dirs();
+    thread::spawn(move || {
+        let relevant = |event: &notify::Result<notify::Event>| match event {
+            Ok(event) => event
+                .paths
+                .iter()
+                .any(|p| is_status_relevant(p, &root, &git_dir, &ignored_dirs)),
+            Err(_) => false,
+        };
+
+        // Exits once the watcher (and with it the sender) is dropped
+        while let Ok(event) = rx.recv() {
+            if !relevant(&event) {
+                continue;
+            }
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+            let _ = app.emit("git-status-changed", &event_path);
+        }
+    });
+
+    watchers.insert(path, watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unwatch_git_status(
+    path: String,
+    state: tauri::State<'_, GitWatcherMap>,
+) -> Result<(), String> {
+    let mut watchers = state.lock().unwrap();
+    watchers.remove(&path);
+    Ok(())
+}
+
+/// How an event affected `path`. Renames show up as the old path going away
+/// and the new one appearing.
+fn change_kind(kind: &EventKind, path: &Path) -> Option<&'static str> {
+    match kind {
+        EventKind::Create(_) => Some("created"),
+        EventKind::Remove(_) => Some("removed"),
+        EventKind::Modify(ModifyKind::Name(_)) if path.exists() => Some("created"),
+        EventKind::Modify(ModifyKind::Name(_)) => Some("removed"),
+        EventKind::Modify(_) | EventKind::Any | EventKind::Other => Some("modified"),
+        EventKind::Access(_) => None,
+    }
+}
+
+/// Fold an event into the pending changes of a burst, keeping one change
+/// per relative path
+fn collect_change(
+    pending: &mut BTreeMap<String, &'static str>,
+    event: notify::Result<notify::Event>,
+    root: &Path,
+    ignored_dirs: &HashSet<String>,
+) {
+    let Ok(event) = event else {
+        return;
+    };
+    for path in &event.paths {
+        let Ok(rel) = path.strip_prefix(root) else {
+            continue;
+        };
+        let ignored = rel
+            .components()
+            .any(|c| ignored_dirs.contains(c.as_os_str().to_string_lossy().as_ref()));
+        if ignored || rel.as_os_str().is_empty() {
+            continue;
+        }
+        let Some(kind) = change_kind(&event.kind, path) else {
+            continue;
+        };
+
+        let entry = pending
+            .entry(rel.to_string_lossy().to_string())
+            .or_insert(kind);
+        // A file created and then written is still just created
+        if !(*entry == "created" && kind == "modified") {
+            *entry = kind;
+        }
+    }
+}
+
+/// Watch a project tree and emit debounced `project-file-changed` events,
+/// each listing the files created, modified, or removed outside the ignored
+/// directories during one burst. Returns the id to pass to `unwatch_project`.
+#[tauri::command]
+pub fn watch_project(
+    root: String,
+    app: AppHandle,
+    state: tauri::State<'_, ProjectWatcherMap>,
+) -> Result<String, String> {
+    // Events carry resolved paths, so resolve the root to match them
+    let root = std::fs::canonicalize(&root).map_err(|e| format!("{}: {}", root, e))?;
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", root.display()));
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| e.to_string())?;
+
+    let id = NEXT_WATCH_ID.fetch_add(1, Ordering::Relaxed);
+    let watch_id = id.to_string();
+    let ignored_dirs = ignored_dirs();
+    thread::spawn({
+        let watch_id = watch_id.clone();
+        move || {
+            let mut pending = BTreeMap::new();
+            // Exits once the watcher (and with it the sender) is dropped
+            while let Ok(event) = rx.recv() {
+                collect_change(&mut pending, event, &root, &ignored_dirs);
+                let deadline = Instant::now() + MAX_DEBOUNCE;
+                while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+                    match rx.recv_timeout(DEBOUNCE.min(left)) {
+                        Ok(event) => collect_change(&mut pending, event, &root, &ignored_dirs),
+                        Err(_) => break,
+                    }
+                }
+                if pending.is_empty() {
+                    continue;
+                }
+                let changes = std::mem::take(&mut pending)
+                    .into_iter()
+                    .map(|(path, kind)| ProjectFileChange {
+                        path,
+                        kind: kind.to_string(),
+                    })
+                    .collect();
+                let _ = app.emit(
+                    "project-file-changed",
+                    ProjectFileChanges {
+                        watch_id: watch_id.clone(),
+                        changes,
+                    },
+                );
+            }
+        }
+    });
+
+    state.lock().unwrap().insert(id, watcher);
+    Ok(watch_id)
+}
+
+#[tauri::command]
+pub fn unwatch_project(
+    watch_id: String,
+    state: tauri::State<'_, ProjectWatcherMap>,
+) -> Result<(), String> {
+    if let Ok(id) = watch_id.parse::<u64>() {
+        state.lock().unwrap().remove(&id);
+    }
+    Ok(())
+}
@@ -1,5 +1,12 @@
+use crate::config::get_config_dir;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowConfig {
@@ -8,6 +15,16 @@ pub struct WindowConfig {
     pub title: String,
     pub width: Option<f64>,
     pub height: Option<f64>,
+    pub x: Option<f64>, // Outer position in logical pixels
+    pub y: Option<f64>,
+}
+
+// Detached window as saved in the layout file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedWindow {
+    pub label: String,
+    #[serde(flatten)]
+    pub config: WindowConfig,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -17,22 +34,180 @@ pub struct WindowInfo {
     pub id: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+// Main window geometry, in physical pixels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MainWindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+fn main_window_state_path() -> PathBuf {
+    get_config_dir().join("window-state.json")
+}
+
+fn load_main_window_state() -> Option<MainWindowState> {
+    let content = fs::read_to_string(main_window_state_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Persist the main window's geometry. While maximized, only the flag is
+/// updated so the restored (un-maximized) size survives.
+pub fn store_main_window_state(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    if window.is_minimized().unwrap_or(false) {
+        return Ok(());
+    }
+
+    let maximized = window.is_maximized().map_err(|e| e.to_string())?;
+    let state = match (maximized, load_main_window_state()) {
+        (true, Some(previous)) => MainWindowState {
+            maximized,
+            ..previous
+        },
+        _ => {
+            let position = window.outer_position().map_err(|e| e.to_string())?;
+            let size = window.inner_size().map_err(|e| e.to_string())?;
+            MainWindowState {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+            }
+        }
+    };
+
+    let path = main_window_state_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&state).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+// Moves and resizes arrive continuously while dragging; geometry is saved
+// once they've stopped for this long
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+static GEOMETRY_SAVES: OnceLock<Mutex<Sender<()>>> = OnceLock::new();
+
+/// Save the main window's geometry once it stops moving or resizing, so a
+/// crash or force-quit doesn't lose it
+pub fn schedule_main_window_state_save(app: &AppHandle) {
+    let sender = GEOMETRY_SAVES.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<()>();
+        let app = app.clone();
+        thread::spawn(move || {
+            while rx.recv().is_ok() {
+                while rx.recv_timeout(GEOMETRY_SAVE_DELAY).is_ok() {}
+                if let Err(e) = store_main_window_state(&app) {
+                    log::warn!("Failed to save main window state: {}", e);
+                }
+            }
+        });
+        Mutex::new(tx)
+    });
+    let _ = sender.lock().unwrap().send(());
+}
+
+/// Restore the main window's saved geometry on startup. The position is only
+/// applied if it lands on a connected monitor, and the size is clamped to fit.
+pub fn restore_main_window_state(app: &AppHandle) {
+    let Some(state) = load_main_window_state() else {
+        return;
+    };
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let monitors = app.available_monitors().unwrap_or_default();
+
+    let target = monitors.iter().find(|m| {
+        let pos = m.position();
+        let size = m.size();
+        state.x >= pos.x
+            && state.y >= pos.y
+            && state.x < pos.x + size.width as i32
+            && state.y < pos.y + size.height as i32
+    });
+
+    if let Some(monitor) = target {
+        let size = monitor.size();
+        let _ = window.set_size(PhysicalSize::new(
+            state.width.min(size.width),
+            state.height.min(size.height),
+        ));
+        let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    }
+
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
 #[tauri::command]
-pub async fn create_detached_window(
-    app: AppHandle,
-    config: WindowConfig,
-) -> Result<String, String> {
+pub fn save_main_window_state(app: AppHandle) -> Result<(), String> {
+    store_main_window_state(&app)
+}
+
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let primary = app.primary_monitor().map_err(|e| e.to_string())?;
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+
+    Ok(monitors
+        .iter()
+        .map(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+            let is_primary = primary.as_ref().is_some_and(|p| {
+                p.name() == monitor.name()
+                    && p.position().x == position.x
+                    && p.position().y == position.y
+            });
+            MonitorInfo {
+                name: monitor.name().cloned(),
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                scale_factor: monitor.scale_factor(),
+                is_primary,
+            }
+        })
+        .collect())
+}
+
+fn build_detached_window(app: &AppHandle, config: &WindowConfig) -> Result<String, String> {
     let label = format!("{}-{}", config.window_type, config.id);
     let url = format!("index.html?mode={}&id={}", config.window_type, config.id);
 
-    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+    let mut builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(url.into()))
         .title(&config.title)
         .inner_size(
             config.width.unwrap_or(1200.0),
             config.height.unwrap_or(800.0),
-        )
-        .build()
-        .map_err(|e| e.to_string())?;
+        );
+    if let (Some(x), Some(y)) = (config.x, config.y) {
+        builder = builder.position(x, y);
+    }
+    let window = builder.build().map_err(|e| e.to_string())?;
 
     // Explicitly set title after creation to ensure it takes effect
     window.set_title(&config.title).map_err(|e| e.to_string())?;
@@ -40,6 +215,105 @@ pub async fn create_detached_window(
     Ok(label)
 }
 
+#[tauri::command]
+pub async fn create_detached_window(
+    app: AppHandle,
+    config: WindowConfig,
+) -> Result<String, String> {
+    build_detached_window(&app, &config)
+}
+
+fn window_layout_path() -> PathBuf {
+    get_config_dir().join("window-layout.json")
+}
+
+/// Save every detached window's geometry so the layout survives a restart
+pub fn store_window_layout(app: &AppHandle) -> Result<(), String> {
+    let mut saved = Vec::new();
+    for (label, window) in app.webview_windows() {
+        if label == "main" {
+            continue;
+        }
+        let Some((window_type, id)) = label.split_once('-') else {
+            continue;
+        };
+
+        let scale = window.scale_factor().map_err(|e| e.to_string())?;
+        let position = window.outer_position().map_err(|e| e.to_string())?;
+        let size = window.inner_size().map_err(|e| e.to_string())?;
+        saved.push(SavedWindow {
+            config: WindowConfig {
+                window_type: window_type.to_string(),
+                id: id.to_string(),
+                title: window.title().unwrap_or_default(),
+                width: Some(size.width as f64 / scale),
+                height: Some(size.height as f64 / scale),
+                x: Some(position.x as f64 / scale),
+                y: Some(position.y as f64 / scale),
+            },
+            label,
+        });
+    }
+    saved.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let path = window_layout_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&saved).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Re-create the saved detached windows that aren't already open. Saved
+/// positions that no longer land on a connected monitor are dropped. Pane
+/// windows are skipped: they attach to a PTY that didn't survive the restart.
+pub fn restore_detached_windows(app: &AppHandle) -> Result<Vec<String>, String> {
+    let Ok(content) = fs::read_to_string(window_layout_path()) else {
+        return Ok(Vec::new());
+    };
+    let saved: Vec<SavedWindow> = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let monitors = app.available_monitors().unwrap_or_default();
+
+    let mut restored = Vec::new();
+    for SavedWindow { label, mut config } in saved {
+        if config.window_type == "pane" || app.get_webview_window(&label).is_some() {
+            continue;
+        }
+
+        if let (Some(x), Some(y)) = (config.x, config.y) {
+            let on_screen = monitors.iter().any(|m| {
+                let (px, py) = (x * m.scale_factor(), y * m.scale_factor());
+                let pos = m.position();
+                let size = m.size();
+                px >= pos.x as f64
+                    && py >= pos.y as f64
+                    && px < (pos.x + size.width as i32) as f64
+                    && py < (pos.y + size.height as i32) as f64
+            });
+            if !on_screen {
+                config.x = None;
+                config.y = None;
+            }
+        }
+
+        match build_detached_window(app, &config) {
+            Ok(label) => restored.push(label),
+            Err(e) => log::warn!("Failed to restore window {}: {}", label, e),
+        }
+    }
+    Ok(restored)
+}
+
+#[tauri::command]
+pub fn save_window_layout(app: AppHandle) -> Result<(), String> {
+    store_window_layout(&app)
+}
+
+#[tauri::command]
+pub async fn restore_window_layout(app: AppHandle) -> Result<Vec<String>, String> {
+    restore_detached_windows(&app)
+}
+
 #[tauri::command]
 pub fn close_detached_window(app: AppHandle, label: String) -> Result<(), String> {
     if let Some(window) = app.get_webview_window(&label) {
@@ -1,22 +1,56 @@
+use crate::directory::expand_path;
+use crate::git::default_branch;
+use crate::git_timing::TimedCommand;
+use crate::pty::shell_quote;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::fs;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::thread;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorktreeInfo {
     pub path: String,
     pub branch: String,
+    pub base_ref: Option<String>, // Ref the branch was created from, if recorded
+    // Only filled in when list_worktrees is asked for status
+    pub dirty: Option<bool>,
+    pub ahead: Option<i32>, // Relative to the branch's upstream
+    pub behind: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AtermBranch {
+    pub name: String,
+    pub last_commit_timestamp: i64,
+    pub worktree_path: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseRefStatus {
+    pub base_ref: Option<String>,
+    pub valid: bool,
+    pub suggestion: Option<String>, // Fallback when the base no longer resolves
+}
+
+// Prefix for branches created by aterm worktrees
+const BRANCH_PREFIX: &str = "aterm/";
+
+// Per-branch git config key holding the ref a worktree branched from. Kept
+// under branch.<name> so `git branch -m` carries it along.
+const BASE_REF_KEY: &str = "atermBaseRef";
+
 fn ensure_git_repo(path: &str) -> Result<(), String> {
     let output = Command::new("git")
         .args(["-C", path, "rev-parse", "--is-inside-work-tree"])
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if !output.status.success() {
@@ -34,7 +68,7 @@ fn ensure_git_repo(path: &str) -> Result<(), String> {
 fn get_current_branch(project_path: &str) -> Result<String, String> {
     let output = Command::new("git")
         .args(["-C", project_path, "branch", "--show-current"])
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if !output.status.success() {
@@ -59,7 +93,7 @@ fn branch_exists(project_path: &str, branch: &str) -> Result<bool, String> {
             "--quiet",
             &format!("refs/heads/{}", branch),
         ])
-        .status()
+        .timed_status()
         .map_err(|e| e.to_string())?;
 
     Ok(status.success())
@@ -100,28 +134,104 @@ fn generate_suffix(seed: &str) -> String {
     format!("{:03x}", hash & 0xfff)
 }
 
-fn copy_preserved_files(project_path: &Path, worktree_path: &Path) -> Result<(), String> {
+// Fixed color for default branches so they stand out from hashed colors
+const DEFAULT_BRANCH_COLOR: &str = "#8b949e";
+
+fn hsl_to_hex(hue: f64, saturation: f64, lightness: f64) -> String {
+    let c = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let h = hue / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = lightness - c / 2.0;
+    let to_byte = |v: f64| ((v + m) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", to_byte(r), to_byte(g), to_byte(b))
+}
+
+/// Deterministic color for an arbitrary name (branch, author, ...) so every
+/// view renders the same name with the same color
+pub fn hash_color(name: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    let hue = (hasher.finish() % 360) as f64;
+    // Mid saturation/lightness keeps contrast on both dark and light themes
+    hsl_to_hex(hue, 0.65, 0.55)
+}
+
+/// Untracked local files (env, overrides) that a new worktree needs too
+fn is_preserved_file(name: &str) -> bool {
+    name == ".envrc" || name == "docker-compose.override.yml" || name.starts_with(".env")
+}
+
+/// Names of the preserved files at the top level of the project
+fn preserved_files(project_path: &Path) -> Result<Vec<String>, String> {
     let entries = fs::read_dir(project_path).map_err(|e| e.to_string())?;
+    let mut names = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-
         let name = entry.file_name().to_string_lossy().to_string();
-        let should_copy =
-            name == ".envrc" || name == "docker-compose.override.yml" || name.starts_with(".env");
-
-        if should_copy {
-            let dest = worktree_path.join(&name);
-            fs::copy(&path, &dest).map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+        if entry.path().is_file() && is_preserved_file(&name) {
+            names.push(name);
         }
     }
+    names.sort();
+    Ok(names)
+}
+
+fn copy_preserved_files(project_path: &Path, worktree_path: &Path) -> Result<(), String> {
+    for name in preserved_files(project_path)? {
+        let dest = worktree_path.join(&name);
+        fs::copy(project_path.join(&name), &dest)
+            .map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+    }
 
     Ok(())
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreservedSync {
+    copied: Vec<String>,
+    skipped: Vec<String>, // Already present in the worktree
+}
+
+/// Copy preserved files from the project into an existing worktree. Files
+/// the worktree already has are skipped unless `overwrite` is set.
+#[tauri::command]
+pub fn sync_preserved_files(
+    project_path: String,
+    worktree_path: String,
+    overwrite: Option<bool>,
+) -> Result<PreservedSync, String> {
+    let project_dir = PathBuf::from(&project_path);
+    let worktree_dir = PathBuf::from(&worktree_path);
+    if !worktree_dir.is_dir() {
+        return Err(format!("Worktree not found: {}", worktree_path));
+    }
+
+    let mut sync = PreservedSync {
+        copied: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for name in preserved_files(&project_dir)? {
+        let dest = worktree_dir.join(&name);
+        if dest.exists() && !overwrite.unwrap_or(false) {
+            sync.skipped.push(name);
+            continue;
+        }
+        fs::copy(project_dir.join(&name), &dest)
+            .map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+        sync.copied.push(name);
+    }
+    Ok(sync)
+}
+
 #[tauri::command]
 pub fn create_worktree(
     project_path: String,
@@ -152,7 +262,7 @@ pub fn create_worktree(
 
     for attempt in 0..20 {
         let suffix = generate_suffix(&format!("{}-{}", task_name, attempt));
-        let branch = format!("aterm/{}-{}", slug, suffix);
+        let branch = format!("{}{}-{}", BRANCH_PREFIX, slug, suffix);
         let worktree_path = worktrees_root.join(format!("{}-{}", slug, suffix));
 
         if worktree_path.exists() {
@@ -176,42 +286,57 @@ pub fn create_worktree(
                     .ok_or_else(|| "Invalid worktree path".to_string())?,
                 &base_ref,
             ])
-            .status()
+            .timed_status()
             .map_err(|e| e.to_string())?;
 
         if !status.success() {
             return Err("git worktree add failed".to_string());
         }
 
+        let _ = Command::new("git")
+            .args([
+                "-C",
+                &project_path,
+                "config",
+                &format!("branch.{}.{}", branch, BASE_REF_KEY),
+                &base_ref,
+            ])
+            .timed_status();
+
         copy_preserved_files(&project_dir, &worktree_path)?;
 
         return Ok(WorktreeInfo {
             path: worktree_path.to_string_lossy().to_string(),
             branch,
+            base_ref: Some(base_ref),
+            ..Default::default()
         });
     }
 
     Err("Failed to generate unique worktree path".to_string())
 }
 
-#[tauri::command]
-pub fn remove_worktree(worktree_path: String) -> Result<(), String> {
-    let common_dir_output = Command::new("git")
-        .args(["-C", &worktree_path, "rev-parse", "--git-common-dir"])
-        .output()
+fn git_common_dir(worktree_path: &str) -> Result<PathBuf, String> {
+    let output = Command::new("git")
+        .args(["-C", worktree_path, "rev-parse", "--git-common-dir"])
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
-    if !common_dir_output.status.success() {
+    if !output.status.success() {
         return Err("Failed to locate git common dir".to_string());
     }
 
-    let common_dir_raw = String::from_utf8_lossy(&common_dir_output.stdout)
-        .trim()
-        .to_string();
-    let mut common_dir = PathBuf::from(&common_dir_raw);
-    if common_dir.is_relative() {
-        common_dir = PathBuf::from(&worktree_path).join(common_dir);
-    }
+    let common_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(if common_dir.is_relative() {
+        PathBuf::from(worktree_path).join(common_dir)
+    } else {
+        common_dir
+    })
+}
+
+#[tauri::command]
+pub fn remove_worktree(worktree_path: String) -> Result<(), String> {
+    let common_dir = git_common_dir(&worktree_path)?;
 
     let status = Command::new("git")
         .args([
@@ -224,7 +349,7 @@ pub fn remove_worktree(worktree_path: String) -> Result<(), String> {
             "--force",
             &worktree_path,
         ])
-        .status()
+        .timed_status()
         .map_err(|e| e.to_string())?;
 
     if !status.success() {
@@ -234,13 +359,154 @@ pub fn remove_worktree(worktree_path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Relabel an aterm worktree: renames its branch and moves its directory to
+/// match the new task name, keeping the original unique suffix
+#[tauri::command]
+pub fn rename_worktree(
+    worktree_path: String,
+    new_task_name: String,
+) -> Result<WorktreeInfo, String> {
+    ensure_git_repo(&worktree_path)?;
+
+    let branch = get_current_branch(&worktree_path)?;
+    let suffix = branch
+        .strip_prefix(BRANCH_PREFIX)
+        .and_then(|name| name.rsplit_once('-'))
+        .map(|(_, suffix)| suffix.to_string())
+        .ok_or_else(|| format!("Not an aterm worktree branch: {}", branch))?;
+
+    let old_dir = PathBuf::from(&worktree_path);
+    let parent_dir = old_dir
+        .parent()
+        .ok_or_else(|| "Worktree path has no parent".to_string())?;
+
+    let slug = slugify_task_name(&new_task_name);
+    let new_branch = format!("{}{}-{}", BRANCH_PREFIX, slug, suffix);
+    let new_dir = parent_dir.join(format!("{}-{}", slug, suffix));
+    let new_path = new_dir
+        .to_str()
+        .ok_or_else(|| "Invalid worktree path".to_string())?
+        .to_string();
+
+    if new_branch == branch && new_dir == old_dir {
+        let base_ref = branch_base_ref(&worktree_path, &branch);
+        return Ok(WorktreeInfo {
+            path: worktree_path,
+            branch,
+            base_ref,
+            ..Default::default()
+        });
+    }
+    if branch_exists(&worktree_path, &new_branch)? {
+        return Err(format!("Branch {} already exists", new_branch));
+    }
+    if new_dir.exists() {
+        return Err(format!("{} already exists", new_path));
+    }
+
+    let common_dir = git_common_dir(&worktree_path)?;
+    let common_dir = common_dir
+        .to_str()
+        .ok_or_else(|| "Invalid git common dir".to_string())?;
+
+    let output = Command::new("git")
+        .args([
+            "--git-dir",
+            common_dir,
+            "worktree",
+            "move",
+            &worktree_path,
+            &new_path,
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "git worktree move failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let output = Command::new("git")
+        .args(["-C", &new_path, "branch", "-m", &branch, &new_branch])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        // Put the directory back so branch and path stay in sync
+        let _ = Command::new("git")
+            .args([
+                "--git-dir",
+                common_dir,
+                "worktree",
+                "move",
+                &new_path,
+                &worktree_path,
+            ])
+            .timed_status();
+        return Err(format!(
+            "git branch -m failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // `git branch -m` moves the branch's config, recorded base included
+    Ok(WorktreeInfo {
+        base_ref: branch_base_ref(&new_path, &new_branch),
+        path: new_path,
+        branch: new_branch,
+        ..Default::default()
+    })
+}
+
+/// Record whether a worktree has uncommitted changes and how far it is
+/// ahead of/behind its upstream (left empty without one)
+fn fill_worktree_status(worktree: &mut WorktreeInfo) {
+    // Missing (prunable) worktrees have no status to report
+    if !Path::new(&worktree.path).is_dir() {
+        return;
+    }
+
+    if let Ok(output) = Command::new("git")
+        .args(["-C", &worktree.path, "status", "--porcelain"])
+        .timed_output()
+    {
+        if output.status.success() {
+            worktree.dirty = Some(!output.stdout.is_empty());
+        }
+    }
+
+    if let Ok(output) = Command::new("git")
+        .args([
+            "-C",
+            &worktree.path,
+            "rev-list",
+            "--left-right",
+            "--count",
+            "@{upstream}...HEAD",
+        ])
+        .timed_output()
+    {
+        if output.status.success() {
+            let counts = String::from_utf8_lossy(&output.stdout);
+            let parts: Vec<&str> = counts.split_whitespace().collect();
+            if parts.len() == 2 {
+                worktree.behind = parts[0].parse().ok();
+                worktree.ahead = parts[1].parse().ok();
+            }
+        }
+    }
+}
+
 #[tauri::command]
-pub fn list_worktrees(project_path: String) -> Result<Vec<WorktreeInfo>, String> {
+pub fn list_worktrees(
+    project_path: String,
+    with_status: Option<bool>,
+) -> Result<Vec<WorktreeInfo>, String> {
     ensure_git_repo(&project_path)?;
 
     let output = Command::new("git")
         .args(["-C", &project_path, "worktree", "list", "--porcelain"])
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if !output.status.success() {
@@ -258,7 +524,11 @@ pub fn list_worktrees(project_path: String) -> Result<Vec<WorktreeInfo>, String>
                 let branch = current_branch
                     .take()
                     .unwrap_or_else(|| "detached".to_string());
-                results.push(WorktreeInfo { path, branch });
+                results.push(WorktreeInfo {
+                    path,
+                    branch,
+                    ..Default::default()
+                });
             }
             current_path = Some(rest.trim().to_string());
             current_branch = None;
@@ -275,12 +545,114 @@ pub fn list_worktrees(project_path: String) -> Result<Vec<WorktreeInfo>, String>
 
     if let Some(path) = current_path.take() {
         let branch = current_branch.unwrap_or_else(|| "detached".to_string());
-        results.push(WorktreeInfo { path, branch });
+        results.push(WorktreeInfo {
+            path,
+            branch,
+            ..Default::default()
+        });
+    }
+
+    for worktree in &mut results {
+        if worktree.branch != "detached" {
+            worktree.base_ref = branch_base_ref(&project_path, &worktree.branch);
+        }
+    }
+
+    if with_status.unwrap_or(false) {
+        // One git status per worktree adds up, so check them concurrently
+        thread::scope(|scope| {
+            for worktree in &mut results {
+                scope.spawn(|| fill_worktree_status(worktree));
+            }
+        });
     }
 
     Ok(results)
 }
 
+/// Whether anything was ever committed on `branch` after it was created,
+/// judged by its reflog having more than the creation entry
+fn branch_has_own_commits(repo_path: &str, branch: &str) -> bool {
+    Command::new("git")
+        .args([
+            "-C",
+            repo_path,
+            "reflog",
+            "show",
+            "--format=%H",
+            branch,
+            "--",
+        ])
+        .timed_output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().count() > 1)
+        .unwrap_or(false)
+}
+
+/// Run `git worktree prune` to drop records of worktrees deleted from disk,
+/// then list aterm worktrees whose branch is merged into the default branch
+/// as removal candidates. Nothing is removed; see `remove_worktrees`.
+/// Worktrees with uncommitted changes, and branches that never got a
+/// commit of their own, are left out.
+#[tauri::command]
+pub fn prune_worktrees(project_path: String) -> Result<Vec<String>, String> {
+    ensure_git_repo(&project_path)?;
+
+    let output = Command::new("git")
+        .args(["-C", &project_path, "worktree", "prune"])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "git worktree prune failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let default = default_branch(&project_path)
+        .ok_or_else(|| "Could not determine the default branch".to_string())?;
+    let output = Command::new("git")
+        .args([
+            "-C",
+            &project_path,
+            "branch",
+            "--format=%(refname:short)",
+            "--merged",
+            &default,
+            "--list",
+            &format!("{}*", BRANCH_PREFIX),
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err("Failed to list merged branches".to_string());
+    }
+    let merged: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .collect();
+
+    let mut candidates: Vec<WorktreeInfo> = list_worktrees(project_path.clone(), None)?
+        .into_iter()
+        .filter(|wt| merged.contains(&wt.branch))
+        .collect();
+    for worktree in &mut candidates {
+        fill_worktree_status(worktree);
+    }
+    candidates
+        .retain(|wt| wt.dirty == Some(false) && branch_has_own_commits(&project_path, &wt.branch));
+    Ok(candidates.into_iter().map(|wt| wt.path).collect())
+}
+
+/// Remove several worktrees (e.g. the candidates from `prune_worktrees`).
+/// Returns the paths that couldn't be removed.
+#[tauri::command]
+pub fn remove_worktrees(paths: Vec<String>) -> Vec<String> {
+    paths
+        .into_iter()
+        .filter(|path| remove_worktree(path.clone()).is_err())
+        .collect()
+}
+
 #[tauri::command]
 pub fn list_git_branches(project_path: String) -> Result<Vec<String>, String> {
     ensure_git_repo(&project_path)?;
@@ -293,7 +665,7 @@ pub fn list_git_branches(project_path: String) -> Result<Vec<String>, String> {
             "refs/heads",
             "--format=%(refname:short)",
         ])
-        .output()
+        .timed_output()
         .map_err(|e| e.to_string())?;
 
     if !output.status.success() {
@@ -310,3 +682,614 @@ pub fn list_git_branches(project_path: String) -> Result<Vec<String>, String> {
     branches.sort();
     Ok(branches)
 }
+
+/// List aterm-created branches (most recently committed first), noting which
+/// ones are currently checked out in a worktree
+#[tauri::command]
+pub fn list_aterm_branches(path: String) -> Result<Vec<AtermBranch>, String> {
+    ensure_git_repo(&path)?;
+
+    let output = Command::new("git")
+        .args([
+            "-C",
+            &path,
+            "for-each-ref",
+            "--sort=-committerdate",
+            "--format=%(refname:short)|%(committerdate:unix)",
+            &format!("refs/heads/{}", BRANCH_PREFIX),
+        ])
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err("Failed to list branches".to_string());
+    }
+
+    let worktrees = list_worktrees(path, None)?;
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let branches = text
+        .lines()
+        .filter_map(|line| {
+            let (name, timestamp) = line.trim().rsplit_once('|')?;
+            let worktree_path = worktrees
+                .iter()
+                .find(|wt| wt.branch == name)
+                .map(|wt| wt.path.clone());
+            Some(AtermBranch {
+                name: name.to_string(),
+                last_commit_timestamp: timestamp.parse().unwrap_or(0),
+                worktree_path,
+            })
+        })
+        .collect();
+
+    Ok(branches)
+}
+
+/// Find the worktree (if any) that currently has the given branch checked out
+#[tauri::command]
+pub fn branch_worktree(project_path: String, branch: String) -> Result<Option<String>, String> {
+    let branch = branch.trim();
+    let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+
+    let worktrees = list_worktrees(project_path, None)?;
+    Ok(worktrees
+        .into_iter()
+        .find(|wt| wt.branch == branch)
+        .map(|wt| wt.path))
+}
+
+/// The base ref recorded for `branch` when its worktree was created
+fn branch_base_ref(repo_path: &str, branch: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args([
+            "-C",
+            repo_path,
+            "config",
+            "--get",
+            &format!("branch.{}.{}", branch, BASE_REF_KEY),
+        ])
+        .timed_output()
+        .ok()?;
+
+    let base = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && !base.is_empty() {
+        Some(base)
+    } else {
+        None
+    }
+}
+
+/// The base ref recorded when the worktree's branch was created
+pub fn recorded_base_ref(worktree_path: &str) -> Option<String> {
+    let branch = get_current_branch(worktree_path).ok()?;
+    branch_base_ref(worktree_path, &branch)
+}
+
+/// Check that a worktree's base ref (given, or else the recorded one) still
+/// resolves, suggesting the default branch when it doesn't
+#[tauri::command]
+pub fn validate_worktree_base(
+    worktree_path: String,
+    base_ref: Option<String>,
+) -> Result<BaseRefStatus, String> {
+    ensure_git_repo(&worktree_path)?;
+
+    let base_ref = base_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .or_else(|| recorded_base_ref(&worktree_path));
+
+    let valid = match &base_ref {
+        Some(base) => Command::new("git")
+            .args([
+                "-C",
+                &worktree_path,
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", base),
+            ])
+            .timed_output()
+            .map_err(|e| e.to_string())?
+            .status
+            .success(),
+        None => false,
+    };
+
+    Ok(BaseRefStatus {
+        suggestion: if valid {
+            None
+        } else {
+            default_branch(&worktree_path)
+        },
+        base_ref,
+        valid,
+    })
+}
+
+/// Switch the project to `branch` (creating it first when `create` is set).
+/// Refuses branches checked out in another worktree, and lists the files
+/// that block a checkout over local changes so the UI can offer to stash.
+#[tauri::command]
+pub fn checkout_branch(project_path: String, branch: String, create: bool) -> Result<(), String> {
+    ensure_git_repo(&project_path)?;
+    let branch = branch.trim();
+    if branch.is_empty() {
+        return Err("Branch name is empty".to_string());
+    }
+    if branch.starts_with('-') {
+        return Err(format!("Invalid branch name: {}", branch));
+    }
+
+    if !create {
+        if let Some(path) = branch_worktree(project_path.clone(), branch.to_string())? {
+            let same = fs::canonicalize(&path).ok() == fs::canonicalize(&project_path).ok();
+            if same {
+                return Ok(());
+            }
+            return Err(format!(
+                "Branch {} is already checked out in worktree {}",
+                branch, path
+            ));
+        }
+    }
+
+    let mut args = vec!["-C", &project_path, "checkout"];
+    if create {
+        args.push("-b");
+    }
+    args.push(branch);
+
+    let output = Command::new("git")
+        .args(&args)
+        .timed_output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("would be overwritten by checkout") {
+        // Conflicting files are listed tab-indented after the message
+        let files: Vec<&str> = stderr
+            .lines()
+            .filter_map(|line| line.strip_prefix('\t'))
+            .collect();
+        return Err(format!(
+            "Local changes would be overwritten by checkout:\n{}",
+            files.join("\n")
+        ));
+    }
+    if stderr.contains("already exists") {
+        return Err(format!("Branch {} already exists", branch));
+    }
+
+    Err(stderr.trim().to_string())
+}
+
+#[tauri::command]
+pub fn branch_color(name: String) -> String {
+    let name = name.trim();
+    let name = name.strip_prefix("refs/heads/").unwrap_or(name);
+    match name {
+        "main" | "master" => DEFAULT_BRANCH_COLOR.to_string(),
+        _ => hash_color(name),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshResolved {
+    pub alias: String,
+    pub host_name: Option<String>,
+    pub port: u16,
+    pub user: Option<String>,
+    pub identity_file: Option<String>,
+}
+
+// Guards against Include cycles in ssh config
+const MAX_SSH_INCLUDE_DEPTH: usize = 16;
+
+/// Glob match supporting `*` and `?`, as used by ssh config patterns
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), text.chars().collect());
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether a `Host` line's patterns select `alias`. A matching negated
+/// pattern rules the block out regardless of other matches.
+fn ssh_host_matches(patterns: &[&str], alias: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if wildcard_match(negated, alias) => return false,
+            Some(_) => {}
+            None => matched |= wildcard_match(pattern, alias),
+        }
+    }
+    matched
+}
+
+/// Files named by an Include argument. Relative paths are taken from
+/// ~/.ssh, and wildcards are expanded in the file name.
+fn ssh_include_paths(arg: &str) -> Vec<PathBuf> {
+    let path = PathBuf::from(expand_path(arg));
+    let path = if path.is_absolute() {
+        path
+    } else {
+        match dirs::home_dir() {
+            Some(home) => home.join(".ssh").join(path),
+            None => return Vec::new(),
+        }
+    };
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+
+    let Some(parent) = path.parent() else {
+        return Vec::new();
+    };
+    let mut matches: Vec<PathBuf> = fs::read_dir(parent)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| wildcard_match(&name, &e.file_name().to_string_lossy()))
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    matches.sort();
+    matches
+}
+
+/// Collect options that apply to `alias` from one ssh config file, following
+/// Includes. As in ssh, the first value seen for a key wins.
+fn collect_ssh_options(
+    path: &Path,
+    alias: &str,
+    mut active: bool,
+    depth: usize,
+    options: &mut HashMap<String, String>,
+) {
+    if depth > MAX_SSH_INCLUDE_DEPTH {
+        return;
+    }
+    let Ok(contents) = fs::read_to_string(path) else {
+        return;
+    };
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Keywords are separated from arguments by whitespace and/or `=`
+        let split = line
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(line.len());
+        let key = line[..split].to_lowercase();
+        let value = line[split..]
+            .trim_start()
+            .trim_start_matches('=')
+            .trim()
+            .trim_matches('"');
+
+        match key.as_str() {
+            "host" => {
+                let patterns: Vec<&str> = value.split_whitespace().collect();
+                active = ssh_host_matches(&patterns, alias);
+            }
+            // Match criteria aren't evaluated; skip those blocks entirely
+            "match" => active = value.eq_ignore_ascii_case("all"),
+            "include" if active => {
+                for arg in value.split_whitespace() {
+                    for included in ssh_include_paths(arg) {
+                        collect_ssh_options(&included, alias, active, depth + 1, options);
+                    }
+                }
+            }
+            "hostname" | "port" | "user" | "identityfile" if active => {
+                options.entry(key).or_insert_with(|| value.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resolve connection details for a Host alias from ~/.ssh/config. Fields
+/// the config doesn't set are left empty, and the port defaults to 22.
+#[tauri::command]
+pub fn resolve_ssh_host(alias: String) -> Result<SshResolved, String> {
+    let alias = alias.trim().to_string();
+    if alias.is_empty() {
+        return Err("Host alias cannot be empty".to_string());
+    }
+
+    let config_path = dirs::home_dir()
+        .ok_or_else(|| "Could not determine home directory".to_string())?
+        .join(".ssh")
+        .join("config");
+    if !config_path.exists() {
+        return Err("No ssh config found at ~/.ssh/config".to_string());
+    }
+
+    let mut options = HashMap::new();
+    collect_ssh_options(&config_path, &alias, true, 0, &mut options);
+
+    let port = match options.get("port") {
+        Some(port) => port
+            .parse()
+            .map_err(|_| format!("Invalid port in ssh config: {}", port))?,
+        None => 22,
+    };
+
+    Ok(SshResolved {
+        // `%h` stands for the alias itself, e.g. `HostName %h.example.com`
+        host_name: options.remove("hostname").map(|h| h.replace("%h", &alias)),
+        port,
+        user: options.remove("user"),
+        identity_file: options.remove("identityfile").map(|f| expand_path(&f)),
+        alias,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshKey {
+    path: String,     // Private key; the public key is `<path>.pub`
+    key_type: String, // Algorithm from the public key, e.g. "ssh-ed25519"
+    comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshAgentKey {
+    fingerprint: String,
+    key_type: String,
+    comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshAgentStatus {
+    running: bool, // An agent is reachable via SSH_AUTH_SOCK
+    keys: Vec<SshAgentKey>,
+}
+
+/// Key pairs in ~/.ssh: private key files with a matching `.pub` alongside
+#[tauri::command]
+pub fn list_ssh_keys() -> Result<Vec<SshKey>, String> {
+    let ssh_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not determine home directory".to_string())?
+        .join(".ssh");
+    let Ok(entries) = fs::read_dir(&ssh_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut keys: Vec<SshKey> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let pub_path = entry.path();
+            if pub_path.extension().and_then(|ext| ext.to_str()) != Some("pub") {
+                return None;
+            }
+            let private_path = pub_path.with_extension("");
+            if !private_path.is_file() {
+                return None;
+            }
+
+            // Public key format: <type> <base64 key> [comment]
+            let contents = fs::read_to_string(&pub_path).ok()?;
+            let mut fields = contents.lines().next()?.splitn(3, ' ');
+            let key_type = fields.next()?.to_string();
+            fields.next()?;
+            let comment = fields
+                .next()
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty());
+
+            Some(SshKey {
+                path: private_path.to_string_lossy().to_string(),
+                key_type,
+                comment,
+            })
+        })
+        .collect();
+    keys.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(keys)
+}
+
+/// Whether ssh-agent is running and which identities it holds, so remote
+/// forms can offer "use agent" alongside specific keys
+#[tauri::command]
+pub fn get_ssh_agent_status() -> Result<SshAgentStatus, String> {
+    let output = Command::new("ssh-add")
+        .arg("-l")
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    // Exit code 1 means the agent has no identities (and stdout says so in
+    // prose), 2 means no agent
+    match output.status.code() {
+        Some(0) => {}
+        Some(code @ (1 | 2)) => {
+            return Ok(SshAgentStatus {
+                running: code == 1,
+                keys: Vec::new(),
+            });
+        }
+        _ => return Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+    }
+
+    // Each line: <bits> <fingerprint> <comment> (<TYPE>)
+    let keys = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (_bits, rest) = line.split_once(' ')?;
+            let (fingerprint, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+            let (comment, key_type) = match rest.rsplit_once(" (") {
+                Some((comment, key_type)) => (comment, key_type.trim_end_matches(')')),
+                None => (rest, ""),
+            };
+            Some(SshAgentKey {
+                fingerprint: fingerprint.to_string(),
+                key_type: key_type.to_string(),
+                comment: Some(comment.trim().to_string()).filter(|c| !c.is_empty()),
+            })
+        })
+        .collect();
+
+    Ok(SshAgentStatus {
+        running: true,
+        keys,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmuxWindow {
+    session: String,
+    index: u32,
+    name: String,
+    active: bool, // Current window of its session
+    panes: u32,
+}
+
+/// Reject ssh hosts and users that ssh would read as options
+/// (`-oProxyCommand=...`) or that can't be a single argument
+pub fn validate_ssh_target_part(what: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("SSH {} is empty", what));
+    }
+    if value.starts_with('-') || value.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid SSH {}: {}", what, value));
+    }
+    Ok(())
+}
+
+/// Windows of the tmux sessions on a remote host (or just `session`'s), for
+/// picking which one to attach to. Empty when the tmux server isn't running.
+#[tauri::command]
+pub async fn list_remote_tmux(
+    ssh_host: String,
+    ssh_port: Option<u16>,
+    ssh_user: Option<String>,
+    ssh_key_path: Option<String>,
+    session: Option<String>,
+) -> Result<Vec<TmuxWindow>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let ssh_user = ssh_user.filter(|u| !u.trim().is_empty());
+        validate_ssh_target_part("host", &ssh_host)?;
+        if let Some(user) = &ssh_user {
+            validate_ssh_target_part("user", user)?;
+        }
+        let target = match ssh_user {
+            Some(user) => format!("{}@{}", user, ssh_host),
+            None => ssh_host.clone(),
+        };
+
+        // ssh hands the command to the remote shell, so quote it ourselves
+        let scope = match &session {
+            Some(session) => format!("-t {}", shell_quote(session)),
+            None => "-a".to_string(),
+        };
+        let remote_command = format!(
+            "tmux list-windows {} -F {}",
+            scope,
+            shell_quote(
+                "#{session_name}\t#{window_index}\t#{window_name}\t#{window_active}\t#{window_panes}"
+            )
+        );
+
+        let mut cmd = Command::new("ssh");
+        cmd.args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=10"]);
+        if let Some(port) = ssh_port {
+            cmd.args(["-p", &port.to_string()]);
+        }
+        if let Some(key) = ssh_key_path.filter(|k| !k.trim().is_empty()) {
+            cmd.args(["-i", &expand_path(&key)]);
+        }
+        let output = cmd
+            .arg("--")
+            .arg(&target)
+            .arg(remote_command)
+            .output()
+            .map_err(|e| e.to_string())?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // 255 is ssh's own failure; 127 is the remote shell's "not found"
+            return match output.status.code() {
+                Some(255) => Err(format!("SSH to {} failed: {}", target, stderr.trim())),
+                Some(127) => Err(format!("tmux is not installed on {}", ssh_host)),
+                _ if stderr.contains("no server running") || stderr.contains("error connecting") => {
+                    Ok(Vec::new())
+                }
+                _ if stderr.contains("can't find session") => Err(format!(
+                    "tmux session {} not found on {}",
+                    session.unwrap_or_default(),
+                    ssh_host
+                )),
+                _ => Err(stderr.trim().to_string()),
+            };
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(5, '\t');
+                Some(TmuxWindow {
+                    session: parts.next()?.to_string(),
+                    index: parts.next()?.parse().ok()?,
+                    name: parts.next()?.to_string(),
+                    active: parts.next()? == "1",
+                    panes: parts.next()?.parse().unwrap_or(1),
+                })
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ssh_targets_cannot_inject_options() {
+        assert!(validate_ssh_target_part("host", "build-box.local").is_ok());
+        assert!(validate_ssh_target_part("user", "deploy").is_ok());
+        assert!(validate_ssh_target_part("host", "-oProxyCommand=touch /tmp/x").is_err());
+        assert!(validate_ssh_target_part("user", "-lroot").is_err());
+        assert!(validate_ssh_target_part("host", "host -p 22").is_err());
+        assert!(validate_ssh_target_part("host", " ").is_err());
+    }
+}
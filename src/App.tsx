@@ -65,7 +65,7 @@ export default function App() {
     cleanupRemovedProjects,
   } = useLayouts({ config, updateConfig, selectedProject });
 
-  const { detachPane, detachProject } = useDetachedWindows();
+  const { detachPane, detachProject, isProjectDetached } = useDetachedWindows();
 
   const {
     terminals: transientTerminals,
@@ -230,6 +230,8 @@ export default function App() {
   }
 
   const openedProjectsList = config.projects.filter((p) => openedProjects.has(p.id));
+  // Detached projects render in their own window instead
+  const inlineProjectsList = openedProjectsList.filter((p) => !isProjectDetached(p.id));
 
   return (
     <div style={styles.container}>
@@ -294,7 +296,7 @@ export default function App() {
           )}
 
           {/* Project terminals - always render opened ones, hide inactive */}
-          {!selectedTerminalId && openedProjectsList.map((project) => {
+          {!selectedTerminalId && inlineProjectsList.map((project) => {
             const savedLayout = config.layouts.find((l) => l.id === project.layoutId) || config.layouts[0];
             const layout = runtimeLayouts[project.id] || savedLayout;
             const isActive = selectedProject?.id === project.id && !selectedTask;
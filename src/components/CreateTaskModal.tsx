@@ -25,6 +25,7 @@ import { createTask } from "../lib/tasks";
 interface WorktreeInfo {
   path: string;
   branch: string;
+  baseRef: string | null;
 }
 
 interface Props {
@@ -279,6 +279,7 @@ export function TerminalPane({
         invoke("send_bell_notification", {
           title: "aTerm",
           body: `${title} needs attention`,
+          paneId: id,
         }).catch(console.error);
         getCurrentWindow().requestUserAttention(2); // Bounce dock icon
       });
@@ -296,10 +297,24 @@ export function TerminalPane({
       invoke("resize_pty", { id, cols: terminal.cols, rows: terminal.rows }).catch(console.error);
     });
 
-    // Spawn PTY if needed
+    // Spawn PTY if needed. After a webview reload the backend may still be
+    // running it; its retained output is replayed once we're listening.
+    // Live output seen before the replay is part of it, so it's dropped.
+    let reattach = Promise.resolve(false);
+    let awaitingReplay = false;
     if (!spawnedPtys.has(id)) {
       spawnedPtys.add(id);
-      invoke("spawn_pty", { id, cwd, cols: terminal.cols, rows: terminal.rows, command }).catch(console.error);
+      awaitingReplay = true;
+      reattach = invoke("get_pty_info", { id }).then(
+        () => true,
+        () => false
+      );
+      reattach.then((running) => {
+        if (!running) {
+          awaitingReplay = false;
+          invoke("spawn_pty", { id, cwd, cols: terminal.cols, rows: terminal.rows, command }).catch(console.error);
+        }
+      });
     }
 
     // Initial prompt injection with debounce + idle detection
@@ -346,7 +361,8 @@ export function TerminalPane({
     if (isNewInstance || !instance?.unlisten) {
       const decoder = new TextDecoder("utf-8", { fatal: false });
 
-      listen<string>(`pty-output-${id}`, (event) => {
+      const outputListener = listen<string>(`pty-output-${id}`, (event) => {
+        if (awaitingReplay) return;
         const rawData = base64ToUint8Array(event.payload);
         // Write directly to terminal without RAF batching for lower latency
         terminal.write(decoder.decode(rawData, { stream: true }));
@@ -372,10 +388,31 @@ export function TerminalPane({
             // Ignore decode errors
           }
         }
-      }).then((fn) => {
+      });
+
+      const replayListener = listen<{ start: number; end: number; data: string }>(
+        `pty-replay-${id}`,
+        (event) => {
+          awaitingReplay = false;
+          terminal.write(decoder.decode(base64ToUint8Array(event.payload.data), { stream: true }));
+        }
+      );
+
+      Promise.all([outputListener, replayListener]).then(([unlistenOutput, unlistenReplay]) => {
+        const fn = () => {
+          unlistenOutput();
+          unlistenReplay();
+        };
         unlistenFn = fn;
         const inst = terminalInstances.get(id);
         if (inst) inst.unlisten = fn;
+        reattach.then((running) => {
+          if (!running) return;
+          invoke("reattach_pty", { id }).catch((err) => {
+            awaitingReplay = false;
+            console.error(err);
+          });
+        });
       });
     }
 
@@ -462,6 +499,14 @@ export function TerminalPane({
     }
   }, [isFocused, isProjectActive]);
 
+  // "Focus pane" from a bell notification
+  useEffect(() => {
+    const unlisten = listen<string>("focus-pane", (event) => {
+      if (event.payload === id) onFocusRef.current?.();
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [id]);
+
   // File drag and drop
   useEffect(() => {
     if (!isFocused || !isProjectActive) return;
@@ -538,6 +583,17 @@ export function TerminalPane({
     }
   }
 
+  // Keep the PTY's saved title in step with the pane name so it survives restarts
+  const handleRename = (name: string) => {
+    invoke("set_pty_title", { id, title: name }).catch(console.error);
+    onRename?.(name);
+  };
+
+  const handleClose = onClose && (() => {
+    invoke("forget_pane_session", { paneId: id }).catch(console.error);
+    onClose();
+  });
+
   return (
     <div
       className={`flex flex-col flex-1 min-h-0 bg-background rounded-lg border overflow-hidden relative ${isDragging ? "border-primary border-2" : "border-border"}`}
@@ -551,8 +607,8 @@ export function TerminalPane({
         projectColor={projectColor}
         isFocused={isFocused}
         canClose={canClose}
-        onClose={onClose}
-        onRename={onRename}
+        onClose={handleClose}
+        onRename={handleRename}
         triggerRename={triggerRename}
         onTriggerRenameComplete={onTriggerRenameComplete}
         dragHandleProps={dragHandleProps}
@@ -6,6 +6,7 @@ import { DEFAULT_PROFILES } from "../lib/profiles";
 interface WorktreeInfo {
   path: string;
   branch: string;
+  baseRef: string | null;
 }
 
 export function useConfig() {
@@ -7,12 +7,20 @@ import type { TerminalProfile } from "../lib/profiles";
 import type { DetachedPaneConfig } from "../components/DetachedPaneView";
 import type { DetachedProjectConfig } from "../components/DetachedProjectView";
 
+interface WindowInfo {
+  label: string;
+  window_type: string;
+  id: string;
+}
+
 interface WindowConfig {
   window_type: string;
   id: string;
   title: string;
   width?: number;
   height?: number;
+  x?: number;
+  y?: number;
 }
 
 export function useDetachedWindows() {
@@ -21,6 +29,21 @@ export function useDetachedWindows() {
     new Set()
   );
 
+  // Re-open the detached windows from the last session, then track every
+  // detached window that's open so its content isn't rendered here too
+  useEffect(() => {
+    invoke<string[]>("restore_window_layout")
+      .catch((err) => console.warn("Failed to restore detached windows", err))
+      .then(() => invoke<WindowInfo[]>("list_detached_windows"))
+      .then((windows) => {
+        const ids = (type: string) =>
+          windows.filter((w) => w.window_type === type).map((w) => w.id);
+        setDetachedPanes((prev) => new Set([...prev, ...ids("pane")]));
+        setDetachedProjects((prev) => new Set([...prev, ...ids("project")]));
+      })
+      .catch(console.error);
+  }, []);
+
   // Listen for reattach requests from detached windows
   useEffect(() => {
     const unlistenPaneReattach = listen<{ paneId: string }>(